@@ -1,7 +1,7 @@
 use std::fs::read_to_string;
 
 mod parser;
-use parser::Parser;
+use parser::{explain, ErrorFormat, ErrorHandler, Interpreter, Lexer, LintLevel, Linter, NullChecker, Parser, TypeChecker, Vocabulary};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -11,16 +11,107 @@ fn main() {
         return;
     }
 
-    let text = match get_file(&args[1]) {
-        Ok(txt) => txt,
-        Err(e) => {
+    //explain <code> prints the long form of an error instead of running anything
+    if args[1] == "explain" {
+        match args.get(2).map(|code| (code, explain(code))) {
+            Some((_, Some(explanation))) => print!("{}", explanation),
+            Some((code, None)) => {
+                eprintln!("{} isn't an error code!", code);
+                std::process::exit(1);
+            },
+            None => println!("Requires an error code, like N0001!")
+        }
+        return;
+    }
+
+    //Every file is a chapter, read in the order given.
+    //--vocabulary <file> adds the phrases in a JSON file to the standard vocabulary
+    //--allow, --warn or --deny <lint> sets what a lint is reported as, they're all warnings otherwise
+    //--error-format=json prints each error as a line of JSON instead of text
+    let mut texts = Vec::new();
+    let mut error_format = ErrorFormat::Human;
+    let mut vocabulary = Vocabulary::standard();
+    let mut linter = Linter::new();
+    let mut paths = args[1..].iter();
+    while let Some(path) = paths.next() {
+        let result = match path.as_str() {
+            "--vocabulary" => match paths.next() {
+                Some(path) => get_file(path).and_then(|json| vocabulary.extend_from_json(&json)
+                    .map_err(|e| format!("{}: {}", path, e))),
+                None => Err("--vocabulary requires path to file!".to_string())
+            },
+            flag if flag.starts_with("--error-format=") => {
+                let name = &flag["--error-format=".len()..];
+                ErrorFormat::from_name(name)
+                    .map(|format| error_format = format)
+                    .ok_or(format!("{} isn't an error format, use human or json!", name))
+            },
+            flag @ ("--allow" | "--warn" | "--deny") => {
+                let level = match flag {
+                    "--allow" => LintLevel::Allow,
+                    "--warn" => LintLevel::Warn,
+                    _ => LintLevel::Deny
+                };
+                match paths.next() {
+                    Some(lint) if linter.set_level(lint, level) => Ok(()),
+                    Some(lint) => Err(format!("{} isn't a lint!", lint)),
+                    None => Err(format!("{} requires a lint, like unused-variables!", flag))
+                }
+            },
+            _ => get_file(path).map(|txt| texts.push((path.as_str(), txt)))
+        };
+        if let Err(e) = result {
             eprintln!("{}",e);
             std::process::exit(1);
         }
-    };
-    let mut parser: Parser = Parser::new(&text);    
-    let tree = &parser.parse();
-    println!("{}\n",tree);
+    }
+    let lexer = Lexer::new(&vocabulary);
+
+    let mut interpreter = Interpreter::new();
+    let mut null_checker = NullChecker::new();
+    let mut type_checker = TypeChecker::new();
+    for (path, text) in &texts {
+        let mut error_handler = ErrorHandler::new(text).with_file(path).with_format(error_format);
+        let mut parser: Parser = Parser::with_lexer(text, &lexer);
+        let program = match parser.parse() {
+            Ok(program) => program,
+            Err(errors) => {
+                for error in errors {
+                    error_handler.report(error);
+                }
+                exit_with_errors(&error_handler);
+            }
+        };
+        println!("{}",program);
+
+        for error in null_checker.check(program.statements()) {
+            error_handler.report(error);
+        }
+        for error in type_checker.check(program.statements()) {
+            error_handler.report(error);
+        }
+        for error in linter.check(program.statements()) {
+            error_handler.report(error);
+        }
+        if error_handler.has_errors() {
+            exit_with_errors(&error_handler);
+        }
+        //Warnings don't stop the chapter from running
+        if !error_handler.errors().is_empty() {
+            error_handler.print_errors();
+            error_handler.take_errors();
+        }
+
+        if let Err(e) = interpreter.run(program.statements()) {
+            error_handler.report(e);
+            exit_with_errors(&error_handler);
+        }
+    }
+}
+
+fn exit_with_errors(error_handler: &ErrorHandler) -> ! {
+    error_handler.print_errors();
+    std::process::exit(1);
 }
 
 fn get_file(path: &str) -> Result<String,String> { 
@@ -0,0 +1,188 @@
+use super::{Error, LineIndex, Severity};
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/*
+    An error the way rustc shows them, the source line it's on with the offending text underlined:
+
+    error[N0001]: division by zero
+     --> story.novel:1:37
+      |
+    1 | There is a number called X, it is 1 / 0.
+      |                                     ^
+      = help: check the divisor isn't zero with an if first
+*/
+pub fn render(error: &Error, lines: &LineIndex, file: &str, color: bool) -> String {
+    let paint = |style: &str, text: &str| if color { format!("{}{}{}", style, text, RESET) } else { text.to_string() };
+
+    let source = lines.line_text(error.line);
+    let line_start = lines.offset(error.line, 1);
+    let (start, end) = error.span.unwrap_or_else(|| {
+        let start = lines.offset(error.line, error.column);
+        (start, start)
+    });
+    //Only the part of the span on the error's line is underlined, always at least one caret
+    let underlined = start.saturating_sub(line_start).min(source.len())..end.saturating_sub(line_start).min(source.len());
+    let carets = source.get(underlined).map_or(0, |text| text.chars().count()).max(1);
+    //Tabs are kept so the carets line up however wide the terminal shows them
+    let padding: String = source
+        .chars()
+        .take(error.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    //Warnings and notes are colored like rustc's
+    let severity_color = match error.severity {
+        Severity::Error => RED,
+        Severity::Warning => YELLOW,
+        Severity::Note => GREEN
+    };
+
+    let number = error.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let mut rendered = format!(
+        "{}{}\n{}{} {}:{}:{}\n{} {}\n{} {} {}\n{} {} {}{}",
+        paint(severity_color, &format!("{}[{}]", error.severity, error.error_type.code())), paint(BOLD, &format!(": {}", error.message())),
+        gutter, paint(BLUE, "-->"), file, error.line, error.column,
+        gutter, paint(BLUE, "|"),
+        paint(BLUE, &number), paint(BLUE, "|"), source,
+        gutter, paint(BLUE, "|"), padding, paint(severity_color, &"^".repeat(carets))
+    );
+    //Says how to change what a lint is reported as
+    if let Some(lint) = error.error_type.lint_name() {
+        let change = match error.severity {
+            Severity::Error => format!("--warn {} makes this a warning", lint),
+            _ => format!("--allow {} turns this off", lint)
+        };
+        rendered.push_str(&format!("\n{} {} {}", gutter, paint(BLUE, "="), paint(BOLD, "note:")));
+        rendered.push_str(&format!(" {}", change));
+    }
+    if let Some(help) = error.help() {
+        rendered.push_str(&format!("\n{} {} {}", gutter, paint(BLUE, "="), paint(BOLD, "help:")));
+        rendered.push_str(&format!(" {}", help));
+    }
+    rendered.push('\n');
+    rendered
+}
+
+//An error as one line of JSON, for editors and CI to read instead of the text above
+pub fn render_json(error: &Error, file: &str) -> String {
+    let span = match error.span {
+        Some((start, end)) => json::object! { "start": start, "end": end },
+        None => json::Null
+    };
+    json::object! {
+        "type": error.error_type.get_type(),
+        "code": error.error_type.code(),
+        "severity": error.severity.to_string(),
+        "message": error.message(),
+        "help": error.help(),
+        "file": file,
+        "line": error.line,
+        "column": error.column,
+        "span": span
+    }.dump()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, render_json, Error, LineIndex, Severity};
+    use super::super::{ErrorType, TokenType};
+
+    #[test]
+    fn underlines_the_span() {
+        let lines = LineIndex::new("There is a number called X, it is 1 / 0.");
+        let error = Error::new(ErrorType::DivideByZero, 1, 35).with_span(34, 39);
+        assert_eq!(render(&error, &lines, "story.novel", false), "\
+error[N0001]: division by zero
+ --> story.novel:1:35
+  |
+1 | There is a number called X, it is 1 / 0.
+  |                                   ^^^^^
+  = help: check the divisor isn't zero with an if first
+");
+    }
+
+    #[test]
+    fn points_at_the_column_without_a_span() {
+        let lines = LineIndex::new("1.\n2.\n3.\n4.\n5.\n6.\n7.\n8.\n9.\n\tX + “Y”");
+        let error = Error::new(ErrorType::UnexpectedToken, 10, 6);
+        assert_eq!(render(&error, &lines, "<input>", false), "\
+error[N0007]: this doesn't belong here
+  --> <input>:10:6
+   |
+10 | \tX + “Y”
+   | \t    ^
+");
+    }
+
+    #[test]
+    fn multi_line_spans_stop_at_the_line_end() {
+        let lines = LineIndex::new("X, it is \"First\nsecond");
+        let error = Error::new(ErrorType::UnterminatedString, 1, 10).with_span(9, 22);
+        let rendered = render(&error, &lines, "<input>", false);
+        assert!(rendered.contains("\n  |          ^^^^^^\n"), "\nUnderline should stop at the line end:\n{}\n", rendered);
+    }
+
+    #[test]
+    fn lint_warnings() {
+        let lines = LineIndex::new("There is a number called X, it is 1.");
+        let error = Error::new(ErrorType::UnusedVariable, 1, 26).with_span(25, 26).with_severity(Severity::Warning);
+        assert_eq!(render(&error, &lines, "<input>", false), "\
+warning[N0017]: this is declared but never used
+ --> <input>:1:26
+  |
+1 | There is a number called X, it is 1.
+  |                          ^
+  = note: --allow unused-variables turns this off
+  = help: remove it, or make it public with \"!\" if a later chapter uses it
+");
+        let error = Error::new(ErrorType::ShadowedName, 1, 26).with_span(25, 26).with_severity(Severity::Note);
+        assert!(render(&error, &lines, "<input>", false).starts_with("note[N0020]: this hides a private name from an earlier chapter\n"));
+        assert!(render(&error, &lines, "<input>", true).starts_with("\x1b[1;32mnote[N0020]\x1b[0m"));
+    }
+
+    #[test]
+    fn says_what_is_missing() {
+        let lines = LineIndex::new("There is a number called X it is 20.");
+        let error = Error::new(ErrorType::MissingToken, 1, 28).with_span(27, 32).with_expected(&[TokenType::Comma]);
+        assert_eq!(render(&error, &lines, "<input>", false), "\
+error[N0005]: expected `,`
+ --> <input>:1:28
+  |
+1 | There is a number called X it is 20.
+  |                            ^^^^^
+");
+        let error = error.with_expected(&[TokenType::Dot, TokenType::Bang]);
+        assert!(render(&error, &lines, "<input>", false).contains("expected `.` or `!`\n"));
+        assert!(render(&error, &lines, "<input>", false).contains("= help: every statement ends with"));
+    }
+
+    #[test]
+    fn json() {
+        let error = Error::new(ErrorType::DivideByZero, 1, 35).with_span(34, 39);
+        assert_eq!(render_json(&error, "story.novel"), concat!(
+            r#"{"type":"Divide by Zero","code":"N0001","severity":"error","message":"division by zero","#,
+            r#""help":"check the divisor isn't zero with an if first","file":"story.novel","line":1,"column":35,"#,
+            r#""span":{"start":34,"end":39}}"#
+        ));
+        let error = Error::new(ErrorType::ShadowedName, 2, 1).with_severity(Severity::Warning);
+        let parsed = json::parse(&render_json(&error, "<input>")).unwrap();
+        assert_eq!(parsed["severity"], "warning");
+        assert!(parsed["span"].is_null());
+    }
+
+    #[test]
+    fn colors() {
+        let lines = LineIndex::new("1 / 0.");
+        let error = Error::new(ErrorType::DivideByZero, 1, 1);
+        let rendered = render(&error, &lines, "<input>", true);
+        assert!(rendered.starts_with("\x1b[1;31merror[N0001]\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+    }
+}
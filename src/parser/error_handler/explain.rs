@@ -0,0 +1,287 @@
+use super::ErrorType;
+
+/*
+    The long form of an error, what `novel explain N0001` prints:
+    what the error means, a story that makes the mistake and the same story fixed.
+*/
+pub fn explain(code: &str) -> Option<String> {
+    let error_type = ErrorType::from_code(code)?;
+    let (description, example) = explanation(error_type);
+    let mut explained = format!("{}: {}\n\n{}\n", error_type.code(), error_type.get_type(), description);
+    if let Some((mistake, fix)) = example {
+        //A story told over several chapters shows which file each one is in
+        let heading = if mistake.len() > 1 { ", with each chapter in its own file, run as `novel chapter1.novel chapter2.novel`" } else { "" };
+        explained.push_str(&format!("\nFor example{}:\n\n{}\nInstead:\n\n{}", heading, story(mistake), story(fix)));
+    }
+    Some(explained)
+}
+
+//A story as the text of each of its chapters
+type Story = &'static [&'static str];
+
+fn story(chapters: Story) -> String {
+    match chapters {
+        [chapter] => format!("    {}\n", chapter),
+        chapters => chapters
+            .iter()
+            .enumerate()
+            .map(|(i, chapter)| format!("    chapter{}.novel: {}\n", i + 1, chapter))
+            .collect()
+    }
+}
+
+//What the error means, and a story with the mistake next to the same story without it
+fn explanation(error_type: ErrorType) -> (&'static str, Option<(Story, Story)>) {
+    match error_type {
+        ErrorType::DivideByZero => (
+            "A number was divided by zero while the story was running. There's no number that could be the \
+            result, so the story stops.",
+            Some((
+                &["There is a number called Guests, it is 0. There is a number called Slices, it is 12 / Guests. \
+                Slices + 1."],
+                &["There is a number called Guests, it is 0. There is a number called Slices, it is 12. \
+                If Guests is not 0, Slices, it is 12 / Guests. Slices + 1."]
+            ))
+        ),
+        ErrorType::InvalidOperands => (
+            "An operator was given values it can't work with while the story was running, like comparing a \
+            number to true. Both sides of a comparison have to be the same type, and \"and\", \"or\" and \
+            \"not\" only work on true and false. Before a story runs the type checker reports these as \
+            N0014, and the null checker reports anything that may be none as N0011, so a story that gets \
+            past them can't make this mistake. It's only reported if one of them has missed something, \
+            please report it with the story that caused it.",
+            None
+        ),
+        ErrorType::NotImplemented => (
+            "The story uses something Novel can read but can't run yet, like \"you\". Novel is still young, \
+            check the README for what it supports so far.",
+            Some((
+                &["There is a number called Gold, it is 5. You. Gold + 1."],
+                &["There is a number called Gold, it is 5. Gold + 1."]
+            ))
+        ),
+        ErrorType::UnknownToken => (
+            "Some of the text isn't a word or symbol Novel knows. Keywords are written in lowercase, and only \
+            start with a capital letter at the start of a sentence, anywhere else a capitalized word is a name.",
+            Some((
+                &["There is a number called X, it Is 3. X + 1."],
+                &["There is a number called X, it is 3. X + 1."]
+            ))
+        ),
+        ErrorType::MissingToken => (
+            "A sentence stopped before it was finished, usually because it's missing the punctuation that ends \
+            every statement: \".\", \"!\", \"?\" or \"‽\".",
+            Some((
+                &["There is a number called X, it is 3. X + 1"],
+                &["There is a number called X, it is 3. X + 1."]
+            ))
+        ),
+        ErrorType::InvalidTokenValue => (
+            "A token was read with a value that doesn't make sense for it, like a name that isn't text. It's \
+            never a mistake in the story, so there's no example of one. It's a bug in Novel, please report it \
+            with the story that caused it.",
+            None
+        ),
+        ErrorType::UnexpectedToken => (
+            "A word or symbol turned up somewhere it can't be, like an operator with nothing on one side of it.",
+            Some((
+                &["There is a number called X, it is * 4. X + 1."],
+                &["There is a number called X, it is 3 * 4. X + 1."]
+            ))
+        ),
+        ErrorType::UndefinedVariable => (
+            "A name was used that hasn't been declared. Everything has to be declared with \"There is a\" \
+            before it's used.",
+            Some((
+                &["X, it is 2."],
+                &["There is a number called X, it is 1. X, it is 2. X + 1."]
+            ))
+        ),
+        ErrorType::AlreadyDeclared => (
+            "A name was declared twice. Each name can only be declared once, after that it's given new values \
+            with \"it is\".",
+            Some((
+                &["There is a number called X, it is 1. There is a number called X, it is 2. X + 1."],
+                &["There is a number called X, it is 1. X, it is 2. X + 1."]
+            ))
+        ),
+        ErrorType::PrivateAccess => (
+            "A chapter used a name that's private to an earlier chapter. Every file is a chapter, and only \
+            declarations that end with \"!\" or \"‽\" can be seen by the chapters after them.",
+            Some((
+                &["There is a number called Gold, it is 5. Gold + 1.", "Gold, it is 6."],
+                &["There is a number called Gold, it is 5! Gold + 1.", "Gold, it is 6."]
+            ))
+        ),
+        ErrorType::UncheckedNull => (
+            "Something that may be none was used without checking it first. Declarations that end with \"?\" \
+            or \"‽\" may be none, and can only be used where an if has checked they aren't.",
+            Some((
+                &["There is a number called X, it is 5? X + 1."],
+                &["There is a number called X, it is 5? If X is not none, X + 1."]
+            ))
+        ),
+        ErrorType::NullAssignment => (
+            "None was given to something that was declared as never being none. Only declarations that end \
+            with \"?\" or \"‽\" may be none.",
+            Some((
+                &["There is a number called X, it is none. X + 1."],
+                &["There is a number called X, it is none? If X is not none, X + 1."]
+            ))
+        ),
+        ErrorType::TypeMismatch => (
+            "A value of one type was given to something declared as another. A name keeps the type it was \
+            declared with for the whole story.",
+            Some((
+                &["There is a number called Pages, it is \"three\". Pages + 1."],
+                &["There is a number called Pages, it is 3. Pages + 1."]
+            ))
+        ),
+        ErrorType::InvalidOperandType => (
+            "An operator was used on a value of a type it doesn't work with, checked before the story runs. \
+            Arithmetic only works on numbers, and \"and\", \"or\" and \"not\" only on booleans.",
+            Some((
+                &["There is a string called Name, it is \"Ada\". There is a number called X, it is Name * 2. X + 1."],
+                &["There is a number called Count, it is 3. There is a number called X, it is Count * 2. X + 1."]
+            ))
+        ),
+        ErrorType::UnterminatedString => (
+            "Dialogue was opened with a quote that's never closed, so the rest of the story was read as part \
+            of it. Quotes inside dialogue have to be escaped with a backslash.",
+            Some((
+                &["There is a string called Greeting, it is \"Good morning.!"],
+                &["There is a string called Greeting, it is \"Good morning.\"!"]
+            ))
+        ),
+        ErrorType::UnterminatedComment => (
+            "An aside was opened with \"[\" that's never closed with \"]\", so the rest of the story was \
+            skipped as part of it.",
+            Some((
+                &["[The hero starts out poor. There is a number called Gold, it is 5. Gold + 1."],
+                &["[The hero starts out poor.] There is a number called Gold, it is 5. Gold + 1."]
+            ))
+        ),
+        ErrorType::UnusedVariable => (
+            "A private name was declared but nothing in its chapter ever reads it. Giving it a new value \
+            doesn't count as using it. Public names aren't reported, since a later chapter may use them. \
+            This is a warning by default.",
+            Some((
+                &["There is a number called Gold, it is 5. There is a number called Silver, it is 2. Gold * 2."],
+                &["There is a number called Gold, it is 5. Gold * 2."]
+            ))
+        ),
+        ErrorType::UnreachableBranch => (
+            "An if chain has a branch after its otherwise. The otherwise runs whenever nothing before it did, \
+            so nothing after it ever can. This is a warning by default.",
+            Some((
+                &["There is a number called Gold, it is 5. If Gold is less than 10, 1; otherwise 2; if Gold is 0, 3."],
+                &["There is a number called Gold, it is 5. If Gold is 0, 3; if Gold is less than 10, 1; otherwise 2."]
+            ))
+        ),
+        ErrorType::PublicNonDeclaration => (
+            "A statement that doesn't declare anything ends with \"!\" or \"‽\". Only declarations can be \
+            public, so it doesn't do anything. This is a warning by default.",
+            Some((
+                &["There is a number called Gold, it is 5. Gold, it is 6! Gold + 1."],
+                &["There is a number called Gold, it is 5. Gold, it is 6. Gold + 1."]
+            ))
+        ),
+        ErrorType::ShadowedName => (
+            "A chapter declared a name an earlier chapter already declared privately. That's allowed since \
+            the earlier one can't be seen, but it's easy to mistake one for the other. It's only a note by \
+            default, use --warn shadowed-names to make it a warning.",
+            Some((
+                &["There is a number called Gold, it is 5. Gold + 1.", "There is a number called Gold, it is 6. Gold + 1."],
+                &["There is a number called Gold, it is 5. Gold + 1.", "There is a number called Silver, it is 6. Silver + 1."]
+            ))
+        ),
+        ErrorType::TooDeeplyNested => (
+            "An expression has more than 50 brackets, \"not\"s or \"-\"s inside each other. Novel stops \
+            there rather than run out of room reading it. Working out the inner part in its own declaration \
+            first brings it back under the limit.",
+            Some((
+                &["There is a number called X, it is (((((((((((((((((((((((((((((((((((((((((((((((((((1))))))))))))))))))))))))))))))))))))))))))))))))))). X + 1."],
+                &["There is a number called Y, it is (1). There is a number called X, it is ((((((((((((((((((((((((((((((((((((((((((((((((((Y)))))))))))))))))))))))))))))))))))))))))))))))))). X + 1."]
+            ))
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explain, explanation, ErrorType, Story};
+    use super::super::Severity;
+    use super::super::super::{Interpreter, Linter, NullChecker, Parser, TypeChecker};
+
+    //Runs a story the way novel does, returning the code of everything reported until it stops
+    fn codes(story: Story) -> Vec<&'static str> {
+        let mut codes = Vec::new();
+        let mut interpreter = Interpreter::new();
+        let mut null_checker = NullChecker::new();
+        let mut type_checker = TypeChecker::new();
+        let mut linter = Linter::new();
+        for text in story {
+            let program = match Parser::new(text).parse() {
+                Ok(program) => program,
+                Err(errors) => {
+                    codes.extend(errors.iter().map(|error| error.error_type.code()));
+                    return codes;
+                }
+            };
+            let mut errors = null_checker.check(program.statements());
+            errors.extend(type_checker.check(program.statements()));
+            errors.extend(linter.check(program.statements()));
+            codes.extend(errors.iter().map(|error| error.error_type.code()));
+            if errors.iter().any(|error| error.severity == Severity::Error) {
+                return codes;
+            }
+            if let Err(error) = interpreter.run(program.statements()) {
+                codes.push(error.error_type.code());
+                return codes;
+            }
+        }
+        codes
+    }
+
+    #[test]
+    fn explains_every_code() {
+        for error_type in ErrorType::ALL {
+            let explanation = explain(error_type.code());
+            assert!(explanation.is_some_and(|text| text.starts_with(&format!("{}: {}", error_type.code(), error_type))),
+                "\n{} has no explanation\n", error_type.code());
+        }
+    }
+
+    #[test]
+    fn chapters_are_files() {
+        let explanation = explain("N0010").unwrap();
+        assert!(explanation.contains("\n    chapter1.novel: There is a number called Gold, it is 5. Gold + 1.\n    chapter2.novel: Gold, it is 6.\n"),
+            "\nEach chapter should be shown as its own file:\n{}\n", explanation);
+    }
+
+    #[test]
+    fn examples_make_their_mistake() {
+        for error_type in ErrorType::ALL {
+            if let (_, Some((mistake, fix))) = explanation(*error_type) {
+                assert_eq!(codes(mistake), [error_type.code()], "\nThe example of {} makes a different mistake\n", error_type.code());
+                assert_eq!(codes(fix), [] as [&str; 0], "\nThe fix for {} isn't clean\n", error_type.code());
+            }
+        }
+    }
+
+    #[test]
+    fn only_unreachable_errors_have_no_example() {
+        let without: Vec<&str> = ErrorType::ALL
+            .iter()
+            .filter(|error_type| explanation(**error_type).1.is_none())
+            .map(|error_type| error_type.code())
+            .collect();
+        assert_eq!(without, ["N0002", "N0006"]);
+    }
+
+    #[test]
+    fn unknown_codes() {
+        assert_eq!(explain("N9999"), None);
+        assert_eq!(explain("Divide by Zero"), None);
+    }
+}
@@ -0,0 +1,115 @@
+use std::cell::Cell;
+
+/*
+    Where every line of a source text starts, so a byte offset can be turned
+    into the line and column a reader would count.
+    Lines and columns both start at 1, and columns count characters, not bytes.
+*/
+#[derive(Clone)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    //Byte offset of the first character of every line
+    starts: Vec<usize>,
+    //The last byte located and its column, the tokenizer asks in order so counting carries on from there
+    last: Cell<(usize, usize)>
+}
+
+#[allow(dead_code)]
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex {
+            text,
+            starts,
+            last: Cell::new((0, 1))
+        }
+    }
+
+    //The line and column of the character at byte
+    pub fn location(&self, byte: usize) -> (u32, usize) {
+        let byte = byte.min(self.text.len());
+        let line = self.starts.partition_point(|&start| start <= byte);
+        let start = self.starts[line - 1];
+        let (last, last_column) = self.last.get();
+        let column = if (start..=byte).contains(&last) {
+            last_column + self.text[last..byte].chars().count()
+        } else {
+            self.text[start..byte].chars().count() + 1
+        };
+        self.last.set((byte, column));
+        (line as u32, column)
+    }
+
+    //The byte offset of a line and column, clamped to the end of the line
+    pub fn offset(&self, line: u32, column: usize) -> usize {
+        let Some(&start) = self.starts.get((line as usize).wrapping_sub(1)) else {
+            return self.text.len();
+        };
+        let text = self.line_text(line);
+        start + text.char_indices().nth(column.saturating_sub(1)).map_or(text.len(), |(i, _)| i)
+    }
+
+    //The text of a line without its line break, empty if there's no such line
+    pub fn line_text(&self, line: u32) -> &'a str {
+        let Some(&start) = self.starts.get((line as usize).wrapping_sub(1)) else {
+            return "";
+        };
+        let end = self.starts.get(line as usize).map_or(self.text.len(), |&next| next - 1);
+        self.text[start..end].trim_end_matches('\r')
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineIndex;
+
+    #[test]
+    fn locations() {
+        let lines = LineIndex::new("One.\nTwo, three.\r\n\nFin‽ X");
+        assert_eq!(lines.location(0), (1, 1));
+        assert_eq!(lines.location(3), (1, 4));
+        assert_eq!(lines.location(4), (1, 5));
+        assert_eq!(lines.location(5), (2, 1));
+        assert_eq!(lines.location(10), (2, 6));
+        assert_eq!(lines.location(18), (3, 1));
+        assert_eq!(lines.location(19), (4, 1));
+        //‽ is three bytes but one column
+        assert_eq!(lines.location(26), (4, 6));
+        assert_eq!(lines.line_count(), 4);
+        assert_eq!(lines.offset(2, 6), 10);
+        assert_eq!(lines.offset(4, 6), 26);
+        assert_eq!(lines.offset(1, 40), 4);
+        assert_eq!(lines.offset(9, 1), 27);
+    }
+
+    #[test]
+    fn long_single_line() {
+        //Every block is written on one line, so locating each token in turn has to stay linear
+        let text = "There is a number called X, it is 1‽ ".repeat(20_000);
+        let lines = LineIndex::new(&text);
+        let start = std::time::Instant::now();
+        for (column, (byte, _)) in text.char_indices().enumerate() {
+            assert_eq!(lines.location(byte), (1, column + 1));
+        }
+        assert!(start.elapsed().as_secs() < 5, "\nLocating every character took {:?}\n", start.elapsed());
+        //Going back still counts from the start of the line
+        assert_eq!(lines.location(40), (1, 39));
+    }
+
+    #[test]
+    fn line_text() {
+        let lines = LineIndex::new("One.\nTwo, three.\r\n\nFin");
+        assert_eq!(lines.line_text(1), "One.");
+        assert_eq!(lines.line_text(2), "Two, three.");
+        assert_eq!(lines.line_text(3), "");
+        assert_eq!(lines.line_text(4), "Fin");
+        assert_eq!(lines.line_text(0), "");
+        assert_eq!(lines.line_text(5), "");
+    }
+}
@@ -1,11 +1,70 @@
-pub struct ErrorHandler {
-    errors: Vec<Error>
+use std::io::IsTerminal;
+use std::rc::Rc;
+
+use super::tokenizer::{Token, TokenType};
+
+mod line_index;
+pub use line_index::LineIndex;
+
+mod diagnostic;
+pub use diagnostic::{render, render_json};
+
+mod explain;
+pub use explain::explain;
+
+pub struct ErrorHandler<'a> {
+    errors: Vec<Error>,
+    lines: Rc<LineIndex<'a>>,
+    //Shown in diagnostics, <input> if it isn't known
+    file: &'a str,
+    format: ErrorFormat
+}
+
+//How errors are printed, as text for people or as a line of JSON each for tools
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum ErrorFormat {
+    Human,
+    Json
 }
 
-impl ErrorHandler {
-    pub fn new() -> Self {
+impl ErrorFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(ErrorFormat::Human),
+            "json" => Some(ErrorFormat::Json),
+            _ => None
+        }
+    }
+}
+
+impl<'a> ErrorHandler<'a> {
+    //Collects errors found in text
+    pub fn new(text: &'a str) -> Self {
+        Self::with_lines(Rc::new(LineIndex::new(text)))
+    }
+
+    //Shares a line index that's already been built, like the tokenizer's
+    pub fn with_lines(lines: Rc<LineIndex<'a>>) -> Self {
         ErrorHandler {
-            errors: Vec::new()
+            errors: Vec::new(),
+            lines,
+            file: "<input>",
+            format: ErrorFormat::Human
+        }
+    }
+
+    //Names the file the text came from in diagnostics
+    pub fn with_file(self, file: &'a str) -> Self {
+        ErrorHandler {
+            file,
+            ..self
+        }
+    }
+
+    pub fn with_format(self, format: ErrorFormat) -> Self {
+        ErrorHandler {
+            format,
+            ..self
         }
     }
 
@@ -13,54 +72,189 @@ impl ErrorHandler {
         self.errors.push(error)
     }
     
+    //Warnings and notes don't count, only what stops the story from running
     pub fn has_errors(&mut self) -> bool {
-        !self.errors.is_empty()
+        self.errors.iter().any(|err| err.severity == Severity::Error)
+    }
+
+    #[allow(dead_code)]
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
     }
 
-    pub fn throw_errors(&mut self) {
-        for err in &self.errors {
-            eprintln!("\n{}",err);
+    #[allow(dead_code)]
+    pub fn lines(&self) -> &LineIndex<'a> {
+        &self.lines
+    }
+
+    //Every error as a diagnostic pointing into the source, colored if they're going to a terminal
+    pub fn diagnostics(&self, color: bool) -> String {
+        self.errors
+            .iter()
+            .map(|err| render(err, &self.lines, self.file, color))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    //Hands the errors found so far to the caller, leaving none behind
+    pub fn take_errors(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.errors)
+    }
+
+    //Every error as a line of JSON
+    pub fn json_diagnostics(&self) -> String {
+        self.errors
+            .iter()
+            .map(|err| render_json(err, self.file) + "\n")
+            .collect()
+    }
+
+    //Prints every error, warning and note to stderr, what happens after that is up to the caller.
+    //They're colored when stderr is a terminal, since that's where they go even if stdout is piped
+    pub fn print_errors(&self) {
+        match self.format {
+            ErrorFormat::Human => eprintln!("{}", self.diagnostics(std::io::stderr().is_terminal())),
+            ErrorFormat::Json => eprint!("{}", self.json_diagnostics())
         }
-        std::process::exit(1);
     }
 }
 
 
-#[derive(PartialEq,Clone,Copy)]
+#[derive(Clone,Copy,PartialEq)]
 pub struct Error {
     pub error_type: ErrorType,
+    //Both start at 1, column counts characters from the start of the line
     pub line: u32,
-    pub position: usize
+    pub column: usize,
+    //Byte offsets of the text the error is about, if it's known
+    pub span: Option<(usize, usize)>,
+    pub severity: Severity,
+    //What should have been there instead, for a missing token
+    pub expected: &'static [TokenType]
 }
 
 impl Error {
 
-    pub fn new(error_type: ErrorType,line: u32, position: usize) -> Self {
+    pub fn new(error_type: ErrorType,line: u32, column: usize) -> Self {
         Error {
             error_type,
             line,
-            position
+            column,
+            span: None,
+            severity: Severity::Error,
+            expected: &[]
+        }
+    }
+
+    //An error about all of token
+    pub fn at(error_type: ErrorType, token: &Token) -> Self {
+        Error::new(error_type, token.line(), token.column()).with_span(token.start(), token.end())
+    }
+
+    pub fn with_span(self, start: usize, end: usize) -> Self {
+        Error {
+            span: Some((start, end)),
+            ..self
+        }
+    }
+
+    pub fn with_severity(self, severity: Severity) -> Self {
+        Error {
+            severity,
+            ..self
+        }
+    }
+
+    pub fn with_expected(self, expected: &'static [TokenType]) -> Self {
+        Error {
+            expected,
+            ..self
         }
     }
+
+    //The headline, "expected `,`" rather than just that something's missing when it's known what
+    pub fn message(&self) -> String {
+        match self.expected {
+            [] => self.error_type.message().to_string(),
+            [expected] => format!("expected {}", expected.description()),
+            [expected @ .., last] => format!(
+                "expected {} or {}",
+                expected.iter().map(TokenType::description).collect::<Vec<_>>().join(", "),
+                last.description()
+            )
+        }
+    }
+
+    //The end of statement help is only any use when that's what's missing
+    pub fn help(&self) -> Option<&'static str> {
+        match self.error_type {
+            ErrorType::MissingToken if !self.expected.contains(&TokenType::Dot) => None,
+            error_type => error_type.help()
+        }
+    }
+}
+
+//How much an error matters, only errors stop a story from running
+#[allow(dead_code)]
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{}",match &self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        })
+    }
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f,"{}[{}:{}]",self.error_type
-        .get_type(),self.line,self.position)
+        .get_type(),self.line,self.column)
     }
 
 }
 
+//Everything that's compared, so a failed assert shows what differs
 impl std::fmt::Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f,"{}",&self.to_string())
+        write!(f,"{}",&self.to_string())?;
+        if let Some((start, end)) = self.span {
+            write!(f," {}..{}",start,end)?;
+        }
+        if self.severity != Severity::Error {
+            write!(f," ({})",self.severity)?;
+        }
+        if !self.expected.is_empty() {
+            write!(f," {}",self.message())?;
+        }
+        Ok(())
     }
 }
 
-    #[allow(dead_code)]
-#[derive(Clone,Copy,PartialEq)]
-pub enum ErrorType {
+//Declares ErrorType along with ALL, so a new error type can't be left out of it
+macro_rules! error_types {
+    ($($error_type:ident),* $(,)?) => {
+        #[allow(dead_code)]
+        #[derive(Clone,Copy,PartialEq)]
+        pub enum ErrorType {
+            $($error_type),*
+        }
+
+        impl ErrorType {
+            //Every error type, in the order of their codes
+            pub const ALL: &'static [ErrorType] = &[$(Self::$error_type),*];
+        }
+    };
+}
+
+error_types! {
     DivideByZero,
     InvalidOperands,
     NotImplemented,
@@ -68,6 +262,20 @@ pub enum ErrorType {
     MissingToken,
     InvalidTokenValue,
     UnexpectedToken,
+    UndefinedVariable,
+    AlreadyDeclared,
+    PrivateAccess,
+    UncheckedNull,
+    NullAssignment,
+    TypeMismatch,
+    InvalidOperandType,
+    UnterminatedString,
+    UnterminatedComment,
+    UnusedVariable,
+    UnreachableBranch,
+    PublicNonDeclaration,
+    ShadowedName,
+    TooDeeplyNested,
 }
 
 impl std::fmt::Display for ErrorType {
@@ -83,6 +291,101 @@ impl std::fmt::Debug for ErrorType {
 }
 
 impl ErrorType {
+    //Stable codes for looking an error up with `explain`, a new error type gets the next number
+    pub fn code(&self) -> &'static str {
+        match &self {
+            Self::DivideByZero => "N0001",
+            Self::InvalidOperands => "N0002",
+            Self::NotImplemented => "N0003",
+            Self::UnknownToken => "N0004",
+            Self::MissingToken => "N0005",
+            Self::InvalidTokenValue => "N0006",
+            Self::UnexpectedToken => "N0007",
+            Self::UndefinedVariable => "N0008",
+            Self::AlreadyDeclared => "N0009",
+            Self::PrivateAccess => "N0010",
+            Self::UncheckedNull => "N0011",
+            Self::NullAssignment => "N0012",
+            Self::TypeMismatch => "N0013",
+            Self::InvalidOperandType => "N0014",
+            Self::UnterminatedString => "N0015",
+            Self::UnterminatedComment => "N0016",
+            Self::UnusedVariable => "N0017",
+            Self::UnreachableBranch => "N0018",
+            Self::PublicNonDeclaration => "N0019",
+            Self::ShadowedName => "N0020",
+            Self::TooDeeplyNested => "N0021",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|error_type| error_type.code().eq_ignore_ascii_case(code))
+    }
+
+    //Lints are errors that can be allowed, warned about or denied by name on the command line
+    pub fn lint_name(&self) -> Option<&'static str> {
+        match &self {
+            Self::UnusedVariable => Some("unused-variables"),
+            Self::UnreachableBranch => Some("unreachable-branches"),
+            Self::PublicNonDeclaration => Some("public-non-declarations"),
+            Self::ShadowedName => Some("shadowed-names"),
+            _ => None
+        }
+    }
+
+    pub fn from_lint_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|error_type| error_type.lint_name() == Some(name))
+    }
+
+    //What went wrong, as a diagnostic's headline
+    pub fn message(&self) -> &'static str {
+        match &self {
+            Self::DivideByZero => "division by zero",
+            Self::InvalidOperands => "these values can't be used with this operator",
+            Self::NotImplemented => "this isn't supported yet",
+            Self::UnknownToken => "this isn't a word or symbol Novel knows",
+            Self::MissingToken => "something is missing here",
+            Self::InvalidTokenValue => "this token has a value it can't have",
+            Self::UnexpectedToken => "this doesn't belong here",
+            Self::UndefinedVariable => "nothing with this name has been declared",
+            Self::AlreadyDeclared => "this name has already been declared",
+            Self::PrivateAccess => "this name is private to another chapter",
+            Self::UncheckedNull => "this may be none",
+            Self::NullAssignment => "none given to something that can't be none",
+            Self::TypeMismatch => "this isn't the type that was declared",
+            Self::InvalidOperandType => "this operator can't be used on a value of this type",
+            Self::UnterminatedString => "this dialogue is never closed",
+            Self::UnterminatedComment => "this aside is never closed",
+            Self::UnusedVariable => "this is declared but never used",
+            Self::UnreachableBranch => "this branch comes after otherwise, so it never runs",
+            Self::PublicNonDeclaration => "this is public but declares nothing",
+            Self::ShadowedName => "this hides a private name from an earlier chapter",
+            Self::TooDeeplyNested => "this is nested too deeply",
+        }
+    }
+
+    //How it might be fixed
+    pub fn help(&self) -> Option<&'static str> {
+        match &self {
+            Self::DivideByZero => Some("check the divisor isn't zero with an if first"),
+            Self::UnknownToken => Some("keywords are lowercase, and only capitalized at the start of a sentence"),
+            Self::MissingToken => Some("every statement ends with \".\", \"!\", \"?\" or \"‽\""),
+            Self::UndefinedVariable => Some("declare it first, \"There is a number called X, it is 1.\""),
+            Self::AlreadyDeclared => Some("give it a new value with \"X, it is 2.\" instead"),
+            Self::PrivateAccess => Some("end its declaration with \"!\" to make it public"),
+            Self::UncheckedNull => Some("check it first, \"If X is not none, ...\""),
+            Self::NullAssignment => Some("end its declaration with \"?\" if it may be none"),
+            Self::UnterminatedString => Some("close it with a matching quote"),
+            Self::UnterminatedComment => Some("close it with \"]\""),
+            Self::UnusedVariable => Some("remove it, or make it public with \"!\" if a later chapter uses it"),
+            Self::UnreachableBranch => Some("move it before the otherwise"),
+            Self::PublicNonDeclaration => Some("end it with \".\" instead"),
+            Self::ShadowedName => Some("give it a different name"),
+            Self::TooDeeplyNested => Some("work out part of it in a declaration of its own first"),
+            _ => None
+        }
+    }
+
     fn get_type(&self) -> String {
         match &self {
             Self::DivideByZero => "Divide by Zero".to_string(),
@@ -92,6 +395,42 @@ impl ErrorType {
             Self::MissingToken => "Missing Token".to_string(),
             Self::InvalidTokenValue => "Invalid Token Value".to_string(),
             Self::UnexpectedToken => "Unexpected Token".to_string(),
+            Self::UndefinedVariable => "Undefined Variable".to_string(),
+            Self::AlreadyDeclared => "Already Declared".to_string(),
+            Self::PrivateAccess => "Private Access".to_string(),
+            Self::UncheckedNull => "Unchecked Null".to_string(),
+            Self::NullAssignment => "Null Assignment".to_string(),
+            Self::TypeMismatch => "Type Mismatch".to_string(),
+            Self::InvalidOperandType => "Invalid Operand Type".to_string(),
+            Self::UnterminatedString => "Unterminated String".to_string(),
+            Self::UnterminatedComment => "Unterminated Comment".to_string(),
+            Self::UnusedVariable => "Unused Variable".to_string(),
+            Self::UnreachableBranch => "Unreachable Branch".to_string(),
+            Self::PublicNonDeclaration => "Public Non-Declaration".to_string(),
+            Self::ShadowedName => "Shadowed Name".to_string(),
+            Self::TooDeeplyNested => "Too Deeply Nested".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorType;
+
+    #[test]
+    fn codes_are_unique() {
+        for (i, error_type) in ErrorType::ALL.iter().enumerate() {
+            assert_eq!(ErrorType::from_code(error_type.code()), Some(*error_type),
+                "\n{} doesn't map back to {}\n", error_type.code(), error_type);
+            assert_eq!(error_type.code(), format!("N{:04}", i + 1));
         }
+        assert_eq!(ErrorType::from_code("n0001"), Some(ErrorType::DivideByZero));
+    }
+
+    #[test]
+    fn lint_names() {
+        assert_eq!(ErrorType::from_lint_name("unused-variables"), Some(ErrorType::UnusedVariable));
+        assert_eq!(ErrorType::from_lint_name("Unused Variable"), None);
+        assert_eq!(ErrorType::DivideByZero.lint_name(), None);
     }
 }
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use super::{error_handler::{Error, ErrorType}, tree_node::{TreeNode, Visibility}};
+use super::LiteralValue;
+
+//A value in the environment along with who is allowed to see it
+#[derive(Clone,Copy)]
+struct Binding<'a> {
+    val: LiteralValue<'a>,
+    visibility: Visibility,
+    chapter: usize
+}
+
+//Named values, innermost scope last
+pub struct Environment<'a> {
+    scopes: Vec<HashMap<&'a str, Binding<'a>>>,
+    chapter: usize
+}
+
+#[allow(dead_code)]
+impl<'a> Environment<'a> {
+    pub fn new() -> Self {
+        Environment {
+            scopes: vec![HashMap::new()],
+            chapter: 0
+        }
+    }
+
+    //Everything declared from here on belongs to a new chapter
+    pub fn next_chapter(&mut self) {
+        self.chapter += 1;
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    //The global scope is never popped
+    pub fn pop_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    //Returns false if the name is already declared in the innermost scope.
+    //Another chapter's private names don't count since this chapter can't see them
+    pub fn define(&mut self, name: &'a str, val: LiteralValue<'a>, visibility: Visibility) -> bool {
+        let chapter = self.chapter;
+        let scope = self.scopes.last_mut().expect("Environment has no scopes!!!");
+        if let Some(binding) = scope.get(name) {
+            if binding.visibility == Visibility::Public || binding.chapter == chapter {
+                return false;
+            }
+        }
+        scope.insert(name, Binding { val, visibility, chapter });
+        true
+    }
+
+    //Updates the closest declaration of name
+    pub fn assign(&mut self, name: &'a str, val: LiteralValue<'a>) -> Result<(), ErrorType> {
+        let chapter = self.chapter;
+        match self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(name)) {
+            Some(binding) if binding.visibility == Visibility::Private && binding.chapter != chapter => {
+                Err(ErrorType::PrivateAccess)
+            },
+            Some(binding) => {
+                binding.val = val;
+                Ok(())
+            },
+            None => Err(ErrorType::UndefinedVariable)
+        }
+    }
+
+    //Looks up a name as seen from the current chapter
+    pub fn resolve(&self, name: &str) -> Result<LiteralValue<'a>, ErrorType> {
+        match self.scopes.iter().rev().find_map(|scope| scope.get(name)) {
+            Some(binding) if binding.visibility == Visibility::Private && binding.chapter != self.chapter => {
+                Err(ErrorType::PrivateAccess)
+            },
+            Some(binding) => Ok(binding.val),
+            None => Err(ErrorType::UndefinedVariable)
+        }
+    }
+
+    //Looks up a name regardless of visibility
+    pub fn get(&self, name: &str) -> Option<LiteralValue<'a>> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).map(|binding| binding.val))
+    }
+}
+
+pub struct Interpreter<'a> {
+    environment: Environment<'a>
+}
+
+#[allow(dead_code)]
+impl<'a> Interpreter<'a> {
+    pub fn new() -> Self {
+        Interpreter {
+            environment: Environment::new()
+        }
+    }
+
+    pub fn environment(&self) -> &Environment<'a> {
+        &self.environment
+    }
+
+    //Executes every statement in order as a new chapter, stopping at the first error
+    pub fn run(&mut self, statements: &[TreeNode<'a>]) -> Result<(), Error> {
+        self.environment.next_chapter();
+        for statement in statements {
+            self.execute(statement)?;
+        }
+        Ok(())
+    }
+
+    pub fn execute(&mut self, node: &TreeNode<'a>) -> Result<LiteralValue<'a>, Error> {
+        match node {
+            TreeNode::Declaration{identifier: LiteralValue::Identifier(name), visibility, val, token, ..} => {
+                let val = val.eval_with(&self.environment)?;
+                if !self.environment.define(name, val, *visibility) {
+                    return Err(Error::at(ErrorType::AlreadyDeclared, token));
+                }
+                Ok(val)
+            },
+            TreeNode::Assignment{identifier: LiteralValue::Identifier(name), val, token, ..} => {
+                let val = val.eval_with(&self.environment)?;
+                self.environment.assign(name, val).map_err(
+                    |error_type| Error::at(error_type, token)
+                )?;
+                Ok(val)
+            },
+            TreeNode::If{condition, expression, otherwise, token, ..} => {
+                match condition.eval_with(&self.environment)? {
+                    LiteralValue::Boolean(true) => self.execute(expression),
+                    LiteralValue::Boolean(false) => match otherwise {
+                        Some(otherwise) => self.execute(otherwise),
+                        None => Ok(LiteralValue::none())
+                    },
+                    _ => Err(Error::at(ErrorType::InvalidOperands, token))
+                }
+            },
+            TreeNode::Statement{expression, ..} => self.execute(expression),
+            _ => node.eval_with(&self.environment)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interpreter,Environment,LiteralValue,Error,ErrorType,TreeNode,Visibility};
+    use super::super::Parser;
+
+    fn parse_all(text: &str) -> Vec<TreeNode<'_>> {
+        let mut parser = Parser::new(text);
+        let mut statements = Vec::new();
+        while parser.is_more_tokens() {
+            statements.extend(parser.parse_statement());
+        }
+        statements
+    }
+
+    #[test]
+    fn scopes_shadow_and_restore() {
+        let mut env = Environment::new();
+        assert!(env.define("X", LiteralValue::Number(1.0), Visibility::Private));
+        assert!(!env.define("X", LiteralValue::Number(2.0), Visibility::Private));
+        env.push_scope();
+        assert!(env.define("X", LiteralValue::Number(3.0), Visibility::Private));
+        assert_eq!(env.get("X"), Some(LiteralValue::Number(3.0)));
+        env.pop_scope();
+        assert_eq!(env.get("X"), Some(LiteralValue::Number(1.0)));
+    }
+
+    #[test]
+    fn assignment_updates_enclosing_scope() {
+        let mut env = Environment::new();
+        env.define("X", LiteralValue::Number(1.0), Visibility::Private);
+        env.push_scope();
+        assert_eq!(env.assign("X", LiteralValue::Number(5.0)), Ok(()));
+        assert_eq!(env.assign("Y", LiteralValue::Number(5.0)), Err(ErrorType::UndefinedVariable));
+        env.pop_scope();
+        assert_eq!(env.get("X"), Some(LiteralValue::Number(5.0)));
+    }
+
+    #[test]
+    fn runs_statements_in_order() {
+        let statements = parse_all(
+            "There is a number called Width, it is 4. \
+            There is a number called Height, it is Width * 2. \
+            Width, it is Width + Height. \
+            There is a number called Area, it is Width * Height."
+        );
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&statements), Ok(()));
+        assert_eq!(interpreter.environment().get("Width"), Some(LiteralValue::Number(12.0)));
+        assert_eq!(interpreter.environment().get("Area"), Some(LiteralValue::Number(96.0)));
+    }
+
+    #[test]
+    fn undefined_variable() {
+        let statements = parse_all("There is a number called X, it is Y + 1.");
+        let err = Interpreter::new().run(&statements).unwrap_err();
+        assert!(err.error_type == ErrorType::UndefinedVariable);
+        assert_eq!((err.line, err.column), (1, 35));
+
+        let statements = parse_all("X, it is 3.");
+        let err = Interpreter::new().run(&statements).unwrap_err();
+        assert!(err.error_type == ErrorType::UndefinedVariable);
+    }
+
+    #[test]
+    fn not_implemented_points_at_its_token() {
+        let statements = parse_all("1 + 1. You.");
+        let err = Interpreter::new().run(&statements).unwrap_err();
+        assert_eq!(err, Error::new(ErrorType::NotImplemented, 1, 8).with_span(7, 10));
+    }
+
+    #[test]
+    fn redeclaration_in_same_scope() {
+        let statements = parse_all(
+            "There is a number called X, it is 1. There is a number called X, it is 2."
+        );
+        let err = Interpreter::new().run(&statements).unwrap_err();
+        assert!(err.error_type == ErrorType::AlreadyDeclared);
+    }
+
+    #[test]
+    fn public_names_cross_chapters() {
+        let first = parse_all("There is a number called Gold, it is 10! There is a number called Secret, it is 3.");
+        let second = parse_all("There is a number called Total, it is Gold * 2. Gold, it is 0.");
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&first), Ok(()));
+        assert_eq!(interpreter.run(&second), Ok(()));
+        assert_eq!(interpreter.environment().get("Total"), Some(LiteralValue::Number(20.0)));
+        assert_eq!(interpreter.environment().get("Gold"), Some(LiteralValue::Number(0.0)));
+    }
+
+    #[test]
+    fn private_names_stay_in_their_chapter() {
+        let first = parse_all("There is a number called Secret, it is 3.");
+        let read = parse_all("There is a number called Leak, it is Secret + 1.");
+        let write = parse_all("Secret, it is 4.");
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&first), Ok(()));
+
+        let err = interpreter.run(&read).unwrap_err();
+        assert!(err.error_type == ErrorType::PrivateAccess);
+        assert_eq!((err.line, err.column), (1, 38));
+
+        let err = interpreter.run(&write).unwrap_err();
+        assert!(err.error_type == ErrorType::PrivateAccess);
+    }
+
+    #[test]
+    fn chapters_can_reuse_private_names() {
+        let first = parse_all("There is a number called Count, it is 1.");
+        let second = parse_all("There is a number called Count, it is 2.");
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&first), Ok(()));
+        assert_eq!(interpreter.run(&second), Ok(()));
+        assert_eq!(interpreter.environment().get("Count"), Some(LiteralValue::Number(2.0)));
+    }
+
+    #[test]
+    fn if_chain_takes_first_true_branch() {
+        let statements = parse_all(
+            "There is a number called Picked, it is 0. \
+            There is a boolean called Left, it is false. \
+            If Left, Picked, it is 1; if true, Picked, it is 2; otherwise Picked, it is 3. \
+            If Left, Picked, it is Picked * 10; otherwise, Picked, it is Picked + 10."
+        );
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&statements), Ok(()));
+        assert_eq!(interpreter.environment().get("Picked"), Some(LiteralValue::Number(12.0)));
+    }
+
+    #[test]
+    fn if_without_otherwise_does_nothing() {
+        let statements = parse_all(
+            "There is a number called Picked, it is 0. If false, Picked, it is 1."
+        );
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&statements), Ok(()));
+        assert_eq!(interpreter.environment().get("Picked"), Some(LiteralValue::Number(0.0)));
+    }
+}
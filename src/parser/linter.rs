@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use super::{error_handler::{Error, ErrorType, Severity}, tokenizer::{Token, TokenType}, tree_node::{TreeNode, Visibility}};
+use super::LiteralValue;
+
+//What a lint is reported as, if it's reported at all
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum LintLevel {
+    Allow,
+    Note,
+    Warn,
+    Deny
+}
+
+/*
+    Finds things that are allowed but probably aren't what was meant:
+    private names that are never read, branches after an otherwise, "!" on something that declares nothing,
+    and names that hide an earlier chapter's private name.
+    Every lint is a warning unless it's been set to allow or deny, apart from shadowing which is
+    only a note since it's often meant.
+*/
+pub struct Linter<'a> {
+    levels: HashMap<&'static str, LintLevel>,
+    //Every declared name, with its visibility and the chapter it was declared in
+    declared: HashMap<&'a str, (Visibility, usize)>,
+    //Private names declared in this chapter that haven't been read yet, and where they were declared
+    unused: Vec<(&'a str, Token<'a>)>,
+    chapter: usize,
+    errors: Vec<Error>
+}
+
+#[allow(dead_code)]
+impl<'a> Linter<'a> {
+    pub fn new() -> Self {
+        Linter {
+            levels: HashMap::new(),
+            declared: HashMap::new(),
+            unused: Vec::new(),
+            chapter: 0,
+            errors: Vec::new()
+        }
+    }
+
+    //Returns false if lint isn't the name of a lint
+    pub fn set_level(&mut self, lint: &str, level: LintLevel) -> bool {
+        match ErrorType::from_lint_name(lint).and_then(|error_type| error_type.lint_name()) {
+            Some(name) => {
+                self.levels.insert(name, level);
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn level(&self, lint: ErrorType) -> LintLevel {
+        lint.lint_name()
+            .and_then(|name| self.levels.get(name).copied())
+            .unwrap_or(match lint {
+                ErrorType::ShadowedName => LintLevel::Note,
+                _ => LintLevel::Warn
+            })
+    }
+
+    //Lints a chapter at the level each lint is set to. Unused names are only reported once the whole chapter
+    //has been read, and every name declared is remembered so a later chapter shadowing it can be found
+    pub fn check(&mut self, statements: &[TreeNode<'a>]) -> Vec<Error> {
+        self.chapter += 1;
+        for statement in statements {
+            self.check_node(statement);
+        }
+        //Nothing after this chapter can see its private names
+        for (_, token) in std::mem::take(&mut self.unused) {
+            self.report(ErrorType::UnusedVariable, &token);
+        }
+        std::mem::take(&mut self.errors)
+    }
+
+    fn check_node(&mut self, node: &TreeNode<'a>) {
+        match node {
+            TreeNode::Identifier{val: LiteralValue::Identifier(name),..} => {
+                self.unused.retain(|(unused, _)| unused != name);
+            },
+            TreeNode::Declaration{identifier, visibility, val, id_token, ..} => {
+                self.check_node(val);
+                if let LiteralValue::Identifier(name) = identifier {
+                    if let Some((Visibility::Private, chapter)) = self.declared.get(name) {
+                        if *chapter != self.chapter {
+                            self.report(ErrorType::ShadowedName, id_token);
+                        }
+                    }
+                    self.declared.insert(name, (*visibility, self.chapter));
+                    if *visibility == Visibility::Private {
+                        self.unused.push((name, *id_token));
+                    }
+                }
+            },
+            TreeNode::Statement{expression, terminator} => {
+                if matches!(terminator.token_type, TokenType::Bang | TokenType::Interrobang) {
+                    self.report(ErrorType::PublicNonDeclaration, terminator);
+                }
+                self.check_node(expression);
+            },
+            TreeNode::If{condition, expression, otherwise, unreachable, ..} => {
+                self.check_node(condition);
+                self.check_node(expression);
+                if let Some(otherwise) = otherwise {
+                    self.check_node(otherwise);
+                }
+                for branch in unreachable {
+                    self.report(ErrorType::UnreachableBranch, &branch.token());
+                    self.check_node(branch);
+                }
+            },
+
+            TreeNode::Addition{left,right,..} |
+            TreeNode::Subtraction{left,right,..} |
+            TreeNode::Multiplication{left,right,..} |
+            TreeNode::Division{left,right,..} |
+            TreeNode::Modulo{left,right,..} |
+            TreeNode::Less{left,right,..} |
+            TreeNode::Greater{left,right,..} |
+            TreeNode::LessEq{left,right,..} |
+            TreeNode::GreaterEq{left,right,..} |
+            TreeNode::EqTo{left,right,..} |
+            TreeNode::NeqTo{left,right,..} |
+            TreeNode::And{left,right,..} |
+            TreeNode::Or{left,right,..} => {
+                self.check_node(left);
+                self.check_node(right);
+            },
+            TreeNode::Negation{arg,..} |
+            TreeNode::Not{arg,..} => self.check_node(arg),
+            TreeNode::Parens{val,..} |
+            TreeNode::Assignment{val,..} => self.check_node(val),
+            _ => ()
+        }
+    }
+
+    fn report(&mut self, lint: ErrorType, token: &Token<'a>) {
+        let severity = match self.level(lint) {
+            LintLevel::Allow => return,
+            LintLevel::Note => Severity::Note,
+            LintLevel::Warn => Severity::Warning,
+            LintLevel::Deny => Severity::Error
+        };
+        self.errors.push(Error::at(lint, token).with_severity(severity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Linter,LintLevel,Error,ErrorType,Severity};
+    use super::super::Parser;
+
+    fn lint(linter: &mut Linter, text: &'static str) -> Vec<Error> {
+        let mut parser = Parser::new(text);
+        let program = parser.parse().unwrap();
+        linter.check(program.statements())
+    }
+
+    #[test]
+    fn unused_variables() {
+        let mut linter = Linter::new();
+        assert_eq!(lint(&mut linter,
+            "There is a number called X, it is 1. There is a number called Y, it is 2. Y, it is 3. X + 1."),
+            [Error::new(ErrorType::UnusedVariable, 1, 63).with_span(62, 63).with_severity(Severity::Warning)]);
+        //Public names may be used by a later chapter
+        assert_eq!(lint(&mut linter, "There is a number called Z, it is 1!"), []);
+    }
+
+    #[test]
+    fn unreachable_branches() {
+        let mut linter = Linter::new();
+        assert_eq!(lint(&mut linter, "If true, 1; otherwise 2; if false, 3; otherwise 4."),
+            [Error::new(ErrorType::UnreachableBranch, 1, 26).with_span(25, 27).with_severity(Severity::Warning)]);
+        assert_eq!(lint(&mut linter, "If true, 1; if false, 3; otherwise 4."), []);
+    }
+
+    #[test]
+    fn public_non_declarations() {
+        let mut linter = Linter::new();
+        assert_eq!(lint(&mut linter, "1 + 2! There is a number called X, it is 1‽ X * 2‽"),
+            [
+                Error::new(ErrorType::PublicNonDeclaration, 1, 6).with_span(5, 6).with_severity(Severity::Warning),
+                Error::new(ErrorType::PublicNonDeclaration, 1, 50).with_span(51, 54).with_severity(Severity::Warning)
+            ]);
+    }
+
+    #[test]
+    fn shadowed_names() {
+        let mut linter = Linter::new();
+        assert_eq!(lint(&mut linter, "There is a number called X, it is 1. X + 1."), []);
+        assert_eq!(lint(&mut linter, "There is a number called X, it is 2. X + 1."),
+            [Error::new(ErrorType::ShadowedName, 1, 26).with_span(25, 26).with_severity(Severity::Note)]);
+        assert!(linter.set_level("shadowed-names", LintLevel::Warn));
+        assert_eq!(lint(&mut linter, "There is a number called X, it is 3. X + 1."),
+            [Error::new(ErrorType::ShadowedName, 1, 26).with_span(25, 26).with_severity(Severity::Warning)]);
+    }
+
+    #[test]
+    fn levels() {
+        let mut linter = Linter::new();
+        assert!(linter.set_level("unused-variables", LintLevel::Allow));
+        assert!(linter.set_level("public-non-declarations", LintLevel::Deny));
+        assert!(!linter.set_level("unused", LintLevel::Deny));
+        let errors = lint(&mut linter, "There is a number called X, it is 1. 1 + 2!");
+        assert_eq!(errors, [Error::new(ErrorType::PublicNonDeclaration, 1, 43).with_span(42, 43)]);
+        assert_eq!(errors[0].severity, Severity::Error);
+        assert_eq!(linter.level(ErrorType::ShadowedName), LintLevel::Note);
+        assert_eq!(linter.level(ErrorType::UnreachableBranch), LintLevel::Warn);
+    }
+}
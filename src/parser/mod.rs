@@ -1,216 +1,813 @@
 mod tokenizer;
 use tokenizer::{LiteralValue, Token, TokenType, Tokenizer};
+pub use tokenizer::{Lexer, Vocabulary};
 
 mod error_handler;
-use error_handler::{Error, ErrorType, ErrorHandler};
+pub use error_handler::{explain, Error, ErrorFormat, ErrorHandler, ErrorType};
 
 mod tree_node;
-use tree_node::TreeNode;
+use tree_node::{TreeNode, ValueType};
+
+mod interpreter;
+pub use interpreter::Interpreter;
+
+mod program;
+pub use program::Program;
+
+mod null_checker;
+pub use null_checker::NullChecker;
+
+mod type_checker;
+pub use type_checker::TypeChecker;
+
+mod linter;
+pub use linter::{LintLevel, Linter};
+
+//The punctuation that ends a statement
+const END_OF_STATEMENT: &[TokenType] = &[TokenType::Dot, TokenType::Bang, TokenType::Question, TokenType::Interrobang];
+
+//How many brackets, nots and minuses an expression can have inside each other, each one is parsed
+//by recursing so without a limit a deep enough expression overflows the stack
+const MAX_NESTING: usize = 50;
 
 pub struct Parser<'a> {
     tokenizer: Tokenizer<'a>,
-    error_handler: ErrorHandler
+    error_handler: ErrorHandler<'a>,
+    //How many of MAX_NESTING the expression being parsed is inside
+    depth: usize
 }
 
 impl<'a> Parser<'a> {
+    #[allow(dead_code)]
     pub fn new(text: &'a str) -> Self {
+        Self::with_lexer(text, Lexer::standard())
+    }
+
+    //Parses text with a lexer built from a custom vocabulary
+    pub fn with_lexer(text: &'a str, lexer: &'a Lexer) -> Self {
+        Self::from_tokenizer(Tokenizer::with_lexer(text, lexer))
+    }
+
+    //Every token in the tree keeps the whitespace and asides before it, see Tokenizer::lossless.
+    //Program::text puts them back together into the text that was parsed
+    #[allow(dead_code)]
+    pub fn lossless(text: &'a str, lexer: &'a Lexer) -> Self {
+        Self::from_tokenizer(Tokenizer::lossless(text, lexer))
+    }
+
+    fn from_tokenizer(tokenizer: Tokenizer<'a>) -> Self {
+        let error_handler = ErrorHandler::with_lines(tokenizer.lines());
         Parser {
-            tokenizer: Tokenizer::new(text),
-            error_handler: ErrorHandler::new()
+            tokenizer,
+            error_handler,
+            depth: 0
         }
     }
 
-    pub fn parse(&'a mut self) -> String {
-        let result_tree = &self.parse_e();
-        if let Ok(val) =  result_tree.eval() {
-            println!("Result = {}\n", val);
+    //Parses every statement, a statement with an error is skipped so the ones after it are still checked
+    pub fn parse(&mut self) -> Result<Program<'a>, Vec<Error>> {
+        let mut statements = Vec::new();
+        while self.is_more_tokens() {
+            if let Some(statement) = self.parse_statement() {
+                statements.push(statement);
+            }
         }
         if self.error_handler.has_errors() {
-            let _ = &self.error_handler.throw_errors();
+            return Err(self.error_handler.take_errors());
+        }
+        let eof = self.tokenizer.scan_token().expect("Error after the last statement!!!");
+        Ok(Program::new(statements, eof))
+    }
+
+    //Parses the next statement, if it can't be parsed the rest of it is skipped
+    fn parse_statement(&mut self) -> Option<TreeNode<'a>> {
+        let statement = self.parse_sentence();
+        if statement.is_none() {
+            self.synchronize();
+        }
+        statement
+    }
+
+    /*
+        Parse Statement:
+        S -> D | IF EOS | CL EOS
+    */
+    fn parse_sentence(&mut self) -> Option<TreeNode<'a>> {
+        match self.peek_type() {
+            Some(TokenType::Declaration) => {
+                let token = self.tokenizer.scan_token().ok()?;
+                self.parse_declaration(token)
+            },
+            Some(TokenType::If) => {
+                let token = self.tokenizer.scan_token().ok()?;
+                let chain = self.parse_if(token)?;
+                let terminator = self.consume_eos()?;
+                Some(TreeNode::new_statement(chain, terminator))
+            },
+            _ => {
+                let clause = self.parse_clause()?;
+                let terminator = self.consume_eos()?;
+                Some(TreeNode::new_statement(clause, terminator))
+            }
+        }
+    }
+
+    /*
+        Parse Clause:
+        CL -> ASN | E
+    */
+    fn parse_clause(&mut self) -> Option<TreeNode<'a>> {
+        if self.is_assignment_next() {
+            let token = self.tokenizer.scan_token().ok()?;
+            self.parse_assignment(token)
+        } else {
+            self.parse_e()
+        }
+    }
+
+    /*
+        Parse If:
+        IF -> If E, CL {; if E, CL} [; otherwise[,] CL {; BR}]
+        BR -> if E, CL | otherwise[,] CL
+        If has already been consumed and is passed in as token
+    */
+    fn parse_if(&mut self, token: Token<'a>) -> Option<TreeNode<'a>> {
+        let condition = self.parse_e()?;
+        let mut tokens = vec![self.consume(&[TokenType::Comma])?];
+        let expression = self.parse_clause()?;
+        let mut otherwise = None;
+        let mut unreachable = Vec::new();
+        if let Some(TokenType::Semicolon) = self.peek_type() {
+            tokens.push(self.tokenizer.scan_token().ok()?);
+            otherwise = Some(self.parse_branch(&mut tokens)?);
+            //Branches after an otherwise never run, they're parsed so the linter can say so
+            if !matches!(otherwise, Some(TreeNode::If{..})) {
+                while let Some(TokenType::Semicolon) = self.peek_type() {
+                    tokens.push(self.tokenizer.scan_token().ok()?);
+                    unreachable.push(self.parse_branch(&mut tokens)?);
+                }
+            }
+        }
+        Some(TreeNode::new_if(condition, expression, otherwise, unreachable, token, tokens))
+    }
+
+    /*
+        Parse Branch:
+        BR -> if E, CL | otherwise[,] CL
+        The semicolon before it has already been consumed, otherwise and its comma are added to tokens
+    */
+    fn parse_branch(&mut self, tokens: &mut Vec<Token<'a>>) -> Option<TreeNode<'a>> {
+        let next = self.consume(&[TokenType::If, TokenType::Otherwise])?;
+        match next.token_type {
+            TokenType::If => self.parse_if(next),
+            _ => {
+                tokens.push(next);
+                if let Some(TokenType::Comma) = self.peek_type() {
+                    tokens.push(self.tokenizer.scan_token().ok()?);
+                }
+                self.parse_clause()
+            }
         }
-        result_tree.to_string()
     }
+
 /*
     Parse Factor:
-    F -> ID | f64 | -F
+    F -> ID | f64 | string | bool | none | (E)
+    A token that can't start a factor is reported and the statement is given up on
 */
-    fn parse_f(&mut self) -> TreeNode<'a> {
-        let node = match &self.tokenizer.peek() {
-            Ok(_) => {
-                let tkn = &self.tokenizer.scan().unwrap().clone();
-            
-                match tkn.token_type {
-                    TokenType::NumericLiteral => {
-                        TreeNode::new_number(tkn.val, *tkn)
-                    },
-                    TokenType::StringLiteral => {
-                        TreeNode::new_string(tkn.val, *tkn)
-                    },
-                    TokenType::Identifier => {
-                        TreeNode::new_identifier(tkn.val, *tkn)
-                    },
-                    TokenType::Comma => {
-                        TreeNode::new_comma(tkn.val, *tkn)
-                    },
-                    TokenType::Dot => {
-                        TreeNode::new_dot(tkn.val, *tkn)
-                    },
-                    TokenType::Bang => {
-                        TreeNode::new_bang(tkn.val, *tkn)
-                    },
-                    TokenType::Question => {
-                        TreeNode::new_question(tkn.val, *tkn)
-                    },
-                    TokenType::Interrobang => {
-                        TreeNode::new_interrobang(tkn.val, *tkn)
-                    },
-                    TokenType::Semicolon => {
-                        TreeNode::new_semicolon(tkn.val, *tkn)
-                    },
-                    TokenType::Colon => {
-                        TreeNode::new_colon(tkn.val, *tkn)
-                    },
-                    TokenType::LeftParen => {
-                        //If right paren found before left then it should be an unexpected token error 
-                        todo!()
-                    },
-                    TokenType::Minus => {
-                        TreeNode::new_negation(self.parse_f(),*tkn)
-                    },
-                    TokenType::If => {
-                        TreeNode::new_if(
-                            self.parse_e(),
-                            self.parse_e(),
-                            *tkn
-                        )
-                    },
-                    TokenType::Therefore => {
-                        todo!()
-                    },
-                    TokenType::False |
-                    TokenType::True => {
-                        TreeNode::new_bool(tkn.val, *tkn)
-                    },
-                    TokenType::None => {
-                        TreeNode::new_none(tkn.val, *tkn)
-                    },
-                    TokenType::You => {
-                        TreeNode::new_you(tkn.val, *tkn)
-                    },
-                    TokenType::Assignment => {
-                        todo!()
-                    },
-                    TokenType::Declaration => {
-                        todo!()
-                    },
-                    TokenType::IdKeyword => {
-                        todo!()
-                    },
-                    TokenType::Eof => {
-                        TreeNode::new_eof(tkn.val, *tkn)
+    fn parse_f(&mut self) -> Option<TreeNode<'a>> {
+        let tkn = match *self.tokenizer.peek() {
+            //The end of the statement is left for the statement to find, so recovery can stop there
+            Ok(tkn) if matches!(
+                tkn.token_type,
+                TokenType::Dot | TokenType::Bang | TokenType::Question | TokenType::Interrobang
+            ) => {
+                self.error_handler.report(Error::at(ErrorType::UnexpectedToken, &tkn));
+                return None;
+            },
+            Ok(_) => self.tokenizer.scan_token().ok()?,
+            Err(_) => {
+                self.skip_error();
+                return None;
+            }
+        };
+        let node = match tkn.token_type {
+            TokenType::NumericLiteral => {
+                TreeNode::new_number(tkn.val, tkn)
+            },
+            TokenType::StringLiteral => {
+                TreeNode::new_string(tkn.val, tkn)
+            },
+            TokenType::Identifier => {
+                TreeNode::new_identifier(tkn.val, tkn)
+            },
+            TokenType::LeftParen => {
+                let val = self.parse_nested(&tkn, Self::parse_e)?;
+                match self.peek_type() {
+                    Some(TokenType::RightParen) => {
+                        let right = self.tokenizer.scan_token().ok()?;
+                        TreeNode::new_parens(val, tkn, right)
                     },
                     _ => {
-                        let _ = &self.error_handler.report(
-                            Error::new(
-                                ErrorType::UnexpectedToken,
-                                tkn.line(),
-                                tkn.start()
-                            ));
-                        TreeNode::new_empty(tkn.val, *tkn)
+                        //Point at the paren that was never closed, not wherever parsing stopped
+                        self.error_handler.report(Error::at(ErrorType::MissingToken, &tkn).with_expected(&[TokenType::RightParen]));
+                        return None;
                     }
                 }
             },
-            Err(e) => {
-                let _ = &self.error_handler.report(
-                    Error::new(
-                        ErrorType::UnexpectedToken,
-                        e.line,
-                        e.position
-                    )
-                );
-                TreeNode::new_empty(
-                    LiteralValue::none(),
-                    Token::invalid()
-                    )
-                }
-            };
-        print!("{}",node);
-        node
+            TokenType::Therefore => {
+                self.error_handler.report(Error::at(ErrorType::NotImplemented, &tkn));
+                return None;
+            },
+            TokenType::False |
+            TokenType::True => {
+                TreeNode::new_bool(
+                    LiteralValue::new_bool(&matches!(tkn.token_type, TokenType::True)),
+                    tkn
+                )
+            },
+            TokenType::None => {
+                TreeNode::new_none(tkn.val, tkn)
+            },
+            TokenType::You => {
+                TreeNode::new_you(tkn.val, tkn)
+            },
+            TokenType::Eof => {
+                TreeNode::new_eof(tkn.val, tkn)
+            },
+            _ => {
+                self.error_handler.report(Error::at(ErrorType::UnexpectedToken, &tkn));
+                return None;
+            }
+        };
+        Some(node)
     }
+    /*
+        Parse Declaration:
+        D -> DK Type IK ID [, AO E] EOS
+        DK has already been consumed and is passed in as token
+    */
+    fn parse_declaration(&mut self, token: Token<'a>) -> Option<TreeNode<'a>> {
+        let type_tkn = self.consume(&[TokenType::TypeKeyword])?;
+        let var_type = match type_tkn.val {
+            LiteralValue::Keyword(key) => ValueType::from_keyword(key)?,
+            _ => return None
+        };
+        let mut tokens = vec![type_tkn, self.consume(&[TokenType::IdKeyword])?];
+        let id_token = self.consume(&[TokenType::Identifier])?;
+        //"There is a number called X?" declares X without a value
+        let val = match self.peek_type() {
+            Some(TokenType::Dot) |
+            Some(TokenType::Bang) |
+            Some(TokenType::Question) |
+            Some(TokenType::Interrobang) => TreeNode::new_none(LiteralValue::none(), id_token),
+            _ => {
+                tokens.push(self.consume(&[TokenType::Comma])?);
+                tokens.push(self.consume(&[TokenType::Assignment])?);
+                self.parse_e()?
+            }
+        };
+        let identifier = id_token.val;
+        let terminator = self.consume_eos()?;
+        Some(TreeNode::new_declaration(identifier, var_type, val, token, id_token, tokens, terminator))
+    }
+
+    /*
+        Parse Assignment:
+        ASN -> ID, AO E
+        ID has already been consumed and is passed in as id_token
+    */
+    fn parse_assignment(&mut self, id_token: Token<'a>) -> Option<TreeNode<'a>> {
+        let comma = self.consume(&[TokenType::Comma])?;
+        let token = self.consume(&[TokenType::Assignment])?;
+        let val = self.parse_e()?;
+        Some(TreeNode::new_assignment(id_token.val, val, token, vec![id_token, comma]))
+    }
+
+    //An identifier followed by ", it is" starts an assignment rather than an expression
+    fn is_assignment_next(&mut self) -> bool {
+        [TokenType::Identifier, TokenType::Comma, TokenType::Assignment]
+            .iter()
+            .enumerate()
+            .all(|(n, expected)| matches!(self.tokenizer.peek_nth(n), Ok(tkn) if tkn.token_type == *expected))
+    }
+
     /*
         Parse Expression:
-        E = T {+|-T}
+        E -> O
+    */
+    fn parse_e(&mut self) -> Option<TreeNode<'a>> {
+        self.parse_or()
+    }
+
+    /*
+        Parse Or:
+        O -> A {or A}
+    */
+    fn parse_or(&mut self) -> Option<TreeNode<'a>> {
+        let mut e = self.parse_and()?;
+        while let Some(TokenType::Or) = self.peek_type() {
+            let tkn = self.tokenizer.scan_token().ok()?;
+            e = TreeNode::new_or(e, self.parse_and()?, tkn);
+        }
+        Some(e)
+    }
+
+    /*
+        Parse And:
+        A -> N {and N}
+    */
+    fn parse_and(&mut self) -> Option<TreeNode<'a>> {
+        let mut e = self.parse_not()?;
+        while let Some(TokenType::And) = self.peek_type() {
+            let tkn = self.tokenizer.scan_token().ok()?;
+            e = TreeNode::new_and(e, self.parse_not()?, tkn);
+        }
+        Some(e)
+    }
+
+    /*
+        Parse Not:
+        N -> not N | C
     */
-    fn parse_e(&mut self) -> TreeNode<'a> {
-        let mut e = self.parse_f();
+    fn parse_not(&mut self) -> Option<TreeNode<'a>> {
+        if let Some(TokenType::Not) = self.peek_type() {
+            let tkn = self.tokenizer.scan_token().ok()?;
+            return Some(TreeNode::new_not(self.parse_nested(&tkn, Self::parse_not)?, tkn));
+        }
+        self.parse_comparison()
+    }
+
+    /*
+        Parse Comparison:
+        C -> S {is|is equal to|is not|is not equal to|is less than|... S}
+    */
+    fn parse_comparison(&mut self) -> Option<TreeNode<'a>> {
+        let mut e = self.parse_sum()?;
+        while let Some(token_type) = self.peek_type() {
+            let constructor = match token_type {
+                TokenType::EqTo => TreeNode::new_eq_to,
+                TokenType::NeqTo => TreeNode::new_neq_to,
+                TokenType::Less => TreeNode::new_less,
+                TokenType::Greater => TreeNode::new_greater,
+                TokenType::LessEq => TreeNode::new_less_eq,
+                TokenType::GreaterEq => TreeNode::new_greater_eq,
+                _ => break
+            };
+            let tkn = self.tokenizer.scan_token().ok()?;
+            e = constructor(e, self.parse_sum()?, tkn);
+        }
+        Some(e)
+    }
+
+    /*
+        Parse Sum:
+        S -> T {+|- T}
+    */
+    fn parse_sum(&mut self) -> Option<TreeNode<'a>> {
+        let mut e = self.parse_t()?;
+        while let Some(token_type) = self.peek_type() {
+            let constructor = match token_type {
+                TokenType::Plus => TreeNode::new_addition,
+                TokenType::Minus => TreeNode::new_subtraction,
+                _ => break
+            };
+            let tkn = self.tokenizer.scan_token().ok()?;
+            e = constructor(e, self.parse_t()?, tkn);
+        }
+        Some(e)
+    }
+
+    /*
+        Parse Term:
+        T -> U {*|/|% U}
+    */
+    fn parse_t(&mut self) -> Option<TreeNode<'a>> {
+        let mut e = self.parse_u()?;
+        while let Some(token_type) = self.peek_type() {
+            let constructor = match token_type {
+                TokenType::Star => TreeNode::new_multiplication,
+                TokenType::Slash => TreeNode::new_division,
+                TokenType::Mod => TreeNode::new_modulus,
+                _ => break
+            };
+            let tkn = self.tokenizer.scan_token().ok()?;
+            e = constructor(e, self.parse_u()?, tkn);
+        }
+        Some(e)
+    }
+
+    /*
+        Parse Unary:
+        U -> -U | F
+    */
+    fn parse_u(&mut self) -> Option<TreeNode<'a>> {
+        if let Some(TokenType::Minus) = self.peek_type() {
+            let tkn = self.tokenizer.scan_token().ok()?;
+            return Some(TreeNode::new_negation(self.parse_nested(&tkn, Self::parse_u)?, tkn));
+        }
+        self.parse_f()
+    }
+
+    //Parses what's inside tkn, reporting it instead once it's MAX_NESTING deep
+    fn parse_nested(&mut self, tkn: &Token<'a>, parse: fn(&mut Self) -> Option<TreeNode<'a>>) -> Option<TreeNode<'a>> {
+        if self.depth == MAX_NESTING {
+            self.error_handler.report(Error::at(ErrorType::TooDeeplyNested, tkn));
+            return None;
+        }
+        self.depth += 1;
+        let node = parse(self);
+        self.depth -= 1;
+        node
+    }
+
+    //Scans the next token if it is one of the expected types, otherwise reports it as missing
+    fn consume(&mut self, expected: &'static [TokenType]) -> Option<Token<'a>> {
+        match *self.tokenizer.peek() {
+            Ok(tkn) if expected.contains(&tkn.token_type) => self.tokenizer.scan_token().ok(),
+            Ok(tkn) => {
+                self.error_handler.report(Error::at(ErrorType::MissingToken, &tkn).with_expected(expected));
+                None
+            },
+            Err(_) => {
+                self.skip_error();
+                None
+            }
+        }
+    }
+
+    //Scans past text the lexer couldn't read and reports it, so it's only ever reported once
+    fn skip_error(&mut self) {
+        if let Err(e) = self.tokenizer.scan_token() {
+            self.error_handler.report(e);
+        }
+    }
+
+    //Scans the punctuation that ends a statement
+    fn consume_eos(&mut self) -> Option<Token<'a>> {
+        self.consume(END_OF_STATEMENT)
+    }
+
+    //Returns the type of the next token without consuming it
+    fn peek_type(&self) -> Option<TokenType> {
+        match self.tokenizer.peek() {
+            Ok(tkn) => Some(tkn.token_type),
+            Err(_) => None
+        }
+    }
+
+    //Text the lexer couldn't read still needs to be parsed, so its error is reported where it's found
+    pub fn is_more_tokens(&mut self) -> bool {
+        match &self.tokenizer.peek() {
+            Ok(tkn) => !matches!(tkn.token_type, TokenType::Eof),
+            Err(_) => true
+        }
+    }
+
+    //Skips past the punctuation ending the statement an error was found in
+    fn synchronize(&mut self) {
         while self.is_more_tokens() {
-            let token = self.tokenizer.scan();
-            e = match token {
-                Ok(tkn) => {
-                    match tkn.token_type {
-                        TokenType::Plus => {
-                            TreeNode::new_addition(
-                                e,
-                                self.parse_f(),
-                                tkn
-                            )
-                        },
-                        TokenType::Minus => {
-                            TreeNode::new_subtraction(
-                                e,
-                                self.parse_f(),
-                                tkn
-                            )
-                        },
-                        TokenType::Star => {
-                            TreeNode::new_multiplication(
-                                e,
-                                self.parse_f(),
-                                tkn
-                             )
-                        },
-                        TokenType::Slash => {
-                            TreeNode::new_division(
-                                e,
-                                self.parse_f(),
-                                tkn
-                            )
-                        },
-                        TokenType::Mod => {
-                            TreeNode::new_modulus(
-                                e,
-                                self.parse_f(),
-                                tkn
-                            )
-                        },
-                        _ => {
-                            TreeNode::new_empty(LiteralValue::none(), Token::invalid()) 
-                        }
-                    }
+            match self.tokenizer.scan_token() {
+                Ok(tkn) if matches!(
+                    tkn.token_type,
+                    TokenType::Dot | TokenType::Bang | TokenType::Question | TokenType::Interrobang
+                ) => return,
+                Ok(_) => {},
+                Err(e) => self.error_handler.report(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Parser,LiteralValue,TreeNode,ValueType,Error,ErrorType,TokenType,Lexer,END_OF_STATEMENT};
+    use super::tree_node::Visibility;
+
+    fn eval_number(text: &str) -> f64 {
+        let mut parser = Parser::new(text);
+        match parser.parse_e().expect("\nExpected an expression\n").eval() {
+            Ok(LiteralValue::Number(num)) => num,
+            Ok(val) => panic!("\n'{}' evaluated to {} instead of a number\n", text, val),
+            Err(e) => panic!("\nFailed to evaluate '{}': {}\n", text, e)
+        }
+    }
+
+    fn root_type(text: &str) -> String {
+        let mut parser = Parser::new(text);
+        parser.parse_e().expect("\nExpected an expression\n").get_type()
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(eval_number("2 + 3 * 4"), 14.0);
+        assert_eq!(eval_number("2 * 3 + 4"), 10.0);
+        assert_eq!(eval_number("10 - 6 / 2"), 7.0);
+        assert_eq!(eval_number("7 + 10 % 4"), 9.0);
+    }
+
+    #[test]
+    fn same_precedence_is_left_associative() {
+        assert_eq!(eval_number("10 - 4 - 3"), 3.0);
+        assert_eq!(eval_number("24 / 4 / 2"), 3.0);
+    }
+
+    #[test]
+    fn unary_minus_binds_tightest() {
+        assert_eq!(eval_number("-2 * 3"), -6.0);
+        assert_eq!(eval_number("4 - -2"), 6.0);
+    }
+
+    #[test]
+    fn comparison_below_arithmetic() {
+        assert_eq!(root_type("1 + 2 is less than 2 * 3"), "Less Than");
+        assert_eq!(root_type("1 is greater than or equal to 2"), "Greater Than Or Equal To");
+        assert_eq!(root_type("1 is less than or equal to 2"), "Less Than Or Equal To");
+        assert_eq!(root_type("1 is not equal to 2"), "Not Equal To");
+        assert_eq!(root_type("1 is not 2"), "Not Equal To");
+        assert_eq!(root_type("1 is 2"), "Equal To");
+    }
+
+    #[test]
+    fn logical_operator_precedence() {
+        let mut parser = Parser::new("true or not false and 1 is 2");
+        match parser.parse_e().expect("\nExpected an expression\n") {
+            TreeNode::Or{right,..} => match *right {
+                TreeNode::And{left,right,..} => {
+                    assert_eq!(left.get_type(), "Not");
+                    assert_eq!(right.get_type(), "Equal To");
                 },
-                Err(e) => {
-                    let _ = &self.error_handler.report(Error {
-                        error_type: e.error_type,
-                        line: e.line,
-                        position: e.position
-                    });
-                    TreeNode::new_none(LiteralValue::none(), Token::invalid())
-                }
+                node => panic!("\nExpected 'and' under 'or', found {}\n", node.get_type())
+            },
+            node => panic!("\nExpected 'or' at the root, found {}\n", node.get_type())
+        }
+    }
+
+    #[test]
+    fn parens_override_precedence() {
+        assert_eq!(eval_number("(2 + 3) * 4"), 20.0);
+        assert_eq!(eval_number("2 * (10 - (3 + 4))"), 6.0);
+        assert_eq!(eval_number("((((7))))"), 7.0);
+        assert_eq!(root_type("(1 is 2)"), "Parenthesis");
+    }
+
+    #[test]
+    fn unmatched_left_paren_points_at_paren() {
+        let mut parser = Parser::new("1 + (2 * (3 + 4)");
+        assert!(parser.parse_e().is_none());
+        assert_eq!(parser.error_handler.errors(), &[Error::new(ErrorType::MissingToken, 1, 5).with_span(4, 5).with_expected(&[TokenType::RightParen])]);
+    }
+
+    #[test]
+    fn unmatched_right_paren_is_unexpected() {
+        let mut parser = Parser::new(") + 2");
+        assert!(parser.parse_e().is_none());
+        assert_eq!(parser.error_handler.errors(), &[Error::new(ErrorType::UnexpectedToken, 1, 1).with_span(0, 1)]);
+    }
+
+    #[test]
+    fn lossless_tree_keeps_trivia() {
+        let mut parser = Parser::lossless("[Chapter one]\nThere is a number called Gold,\n  it is 20 .", Lexer::standard());
+        match parser.parse_statement().unwrap() {
+            TreeNode::Declaration{token,val,terminator,..} => {
+                assert_eq!(token.trivia(), "[Chapter one]\n");
+                assert_eq!(token.comments().collect::<Vec<_>>(), ["Chapter one"]);
+                assert_eq!(val.token().trivia(), " ");
+                assert_eq!(terminator.trivia(), " ");
+            },
+            node => panic!("\nExpected a declaration, found {}\n", node.get_type())
+        }
+    }
+
+    #[test]
+    fn lossless_tree_rebuilds_the_text() {
+        let texts = [
+            "There is a number [x] called Gold, it is 1.",
+            "[Chapter one]\nThere is a number called Gold,\n  it is 20 . Gold ,  it is ( Gold+1 ) * -2!\n",
+            "There is a boolean called B ? If B is not none ,B, it is not true ; if Gold is less than 2, 3;otherwise , 4 ; if true, 5.  [the end]\n\n"
+        ];
+        for text in texts {
+            let mut parser = Parser::lossless(text, Lexer::standard());
+            let program = parser.parse().unwrap();
+            assert_eq!(program.text(), text, "\nThe tree should have every token of '{}'\n", text);
+        }
+    }
+
+    #[test]
+    fn readme_declaration() {
+        let mut parser = Parser::new("There is a number called Example, it is 20.");
+        match parser.parse_statement().unwrap() {
+            TreeNode::Declaration{identifier,var_type,val,terminator,..} => {
+                assert_eq!(identifier, LiteralValue::Identifier("Example"));
+                assert_eq!(var_type, ValueType::Number);
+                assert_eq!(val.eval(), Ok(LiteralValue::Number(20.0)));
+                assert!(matches!(terminator.token_type, TokenType::Dot));
+            },
+            node => panic!("\nExpected a declaration, found {}\n", node.get_type())
+        }
+        assert!(!parser.error_handler.has_errors());
+    }
+
+    #[test]
+    fn declaration_records_type_and_terminator() {
+        let cases = [
+            ("There is a string called Name, she is 1 + 2!", ValueType::String, TokenType::Bang, Visibility::Public),
+            ("there is a boolean labelled Flag, it is true?", ValueType::Boolean, TokenType::Question, Visibility::Private),
+            ("There is a class named Thing, they are none‽", ValueType::Class, TokenType::Interrobang, Visibility::Public),
+        ];
+        for (text, expected_type, expected_terminator, expected_visibility) in cases {
+            let mut parser = Parser::new(text);
+            match parser.parse_statement().unwrap() {
+                TreeNode::Declaration{var_type,visibility,terminator,..} => {
+                    assert_eq!(var_type, expected_type, "\nWrong type for '{}'\n", text);
+                    assert_eq!(visibility, expected_visibility, "\nWrong visibility for '{}'\n", text);
+                    assert!(terminator.token_type == expected_terminator,
+                        "\nWrong terminator for '{}', found {}\n", text, terminator.token_type);
+                },
+                node => panic!("\nExpected a declaration for '{}', found {}\n", text, node.get_type())
             }
         }
-        e
     }
-    pub fn is_more_tokens(&mut self) -> bool {
-            match &self.tokenizer.peek() {
-                Ok(tkn) => !matches!(tkn.token_type, TokenType::Eof),
-                Err(e) => {
-                    let _ = &self.error_handler.report(Error {
-                        error_type: e.error_type,
-                        line: e.line,
-                        position: e.position 
-                    });
-                    false
+
+    #[test]
+    fn declaration_missing_comma() {
+        let mut parser = Parser::new("There is a number called X it is 20.");
+        assert!(parser.parse_statement().is_none());
+        assert_eq!(parser.error_handler.errors(), &[Error::new(ErrorType::MissingToken, 1, 28).with_span(27, 32).with_expected(&[TokenType::Comma])]);
+    }
+
+    #[test]
+    fn program_is_every_statement() {
+        let mut parser = Parser::new(
+            "1 + 2. There is a number called X, it is 3!\nX, it is X * 2? X is 6‽"
+        );
+        let program = parser.parse().unwrap();
+        let types: Vec<String> = program.statements().iter().map(|node| match node {
+            TreeNode::Statement{expression,..} => expression.get_type(),
+            _ => node.get_type()
+        }).collect();
+        assert_eq!(types, ["Addition", "Declaration", "Assignment", "Equal To"]);
+    }
+
+    #[test]
+    fn recovers_at_the_end_of_a_statement() {
+        let mut parser = Parser::new(
+            "There is a number called X it is 1. X, it is 3 # 4! There is a number called Y, it is 2. Y + ."
+        );
+        assert_eq!(parser.parse().err(), Some(vec![
+            Error::new(ErrorType::MissingToken, 1, 28).with_span(27, 32).with_expected(&[TokenType::Comma]),
+            Error::new(ErrorType::UnknownToken, 1, 48).with_span(47, 48),
+            Error::new(ErrorType::UnexpectedToken, 1, 94).with_span(93, 94)
+        ]));
+    }
+
+    #[test]
+    fn recovery_keeps_every_statement_after_an_error() {
+        let mut parser = Parser::new("1 + 2 3. There is a number called X, it is 3! X, it is X * 2.");
+        assert_eq!(parser.parse().err(), Some(vec![Error::new(ErrorType::MissingToken, 1, 7).with_span(6, 7).with_expected(END_OF_STATEMENT)]));
+        assert!(!parser.is_more_tokens());
+    }
+
+    #[test]
+    fn not_is_only_a_prefix() {
+        let mut parser = Parser::new("1 not 2.");
+        assert_eq!(parser.parse().err(), Some(vec![Error::new(ErrorType::MissingToken, 1, 3).with_span(2, 5).with_expected(END_OF_STATEMENT)]));
+        assert!(eval_bool("not 1 is 2"));
+        assert!(eval_bool("1 isn't 2"));
+    }
+
+    #[test]
+    fn one_bad_token_is_one_error() {
+        let cases = [("1 + * 2. 3.", 5), ("X, it is . 3.", 10), ("(1 + 2 3. 4.", 1), ("X is 3 # 4. 5.", 8)];
+        for (text, column) in cases {
+            let mut parser = Parser::new(text);
+            let errors = parser.parse().err().unwrap_or_default();
+            assert_eq!(errors.len(), 1, "\n'{}' should have one error, found {:?}\n", text, errors);
+            assert_eq!((errors[0].line, errors[0].column), (1, column), "\nWrong place for '{}'\n", text);
+        }
+    }
+
+    #[test]
+    fn punctuation_is_not_a_factor() {
+        let cases = [("X, it is , .", 10), ("1 + ;.", 5), ("1 * :.", 5)];
+        for (text, column) in cases {
+            let mut parser = Parser::new(text);
+            let start = column - 1;
+            assert_eq!(parser.parse().err(), Some(vec![Error::new(ErrorType::UnexpectedToken, 1, column).with_span(start, start + 1)]),
+                "\n'{}' should be an unexpected token\n", text);
+        }
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let limit = "(".repeat(50) + "1" + &")".repeat(50) + ".";
+        assert!(Parser::new(&limit).parse().is_ok(), "\n50 brackets should be allowed\n");
+
+        //Deep enough to overflow the stack without the limit, reported once at the first one past it
+        let cases = [
+            ("(".repeat(2000) + "1" + &")".repeat(2000) + ".", 50, 1),
+            ("not ".repeat(2000) + "true.", 200, 3),
+            ("-".repeat(2000) + "1.", 50, 1),
+        ];
+        for (text, start, len) in cases {
+            let mut parser = Parser::new(&text);
+            assert_eq!(parser.parse().err(), Some(vec![Error::new(ErrorType::TooDeeplyNested, 1, start + 1).with_span(start, start + len)]),
+                "\n'{}...' should be nested too deeply\n", &text[..10]);
+        }
+    }
+
+    #[test]
+    fn statement_needs_terminator() {
+        let mut parser = Parser::new("1 + 2 3.");
+        assert!(parser.parse_statement().is_none());
+        assert_eq!(parser.error_handler.errors(), &[Error::new(ErrorType::MissingToken, 1, 7).with_span(6, 7).with_expected(END_OF_STATEMENT)]);
+    }
+
+    #[test]
+    fn if_chain() {
+        let mut parser = Parser::new(
+            "If A is 1, B, it is 2; if A is 3, 4; otherwise, B, it is 5."
+        );
+        let statement = parser.parse_statement().unwrap();
+        let chain = match statement {
+            TreeNode::Statement{expression,..} => expression,
+            node => panic!("\nExpected a statement, found {}\n", node.get_type())
+        };
+        match *chain {
+            TreeNode::If{condition,expression,otherwise: Some(otherwise),..} => {
+                assert_eq!(condition.get_type(), "Equal To");
+                assert_eq!(expression.get_type(), "Assignment");
+                match *otherwise {
+                    TreeNode::If{expression,otherwise: Some(otherwise),..} => {
+                        assert_eq!(expression.get_type(), "NumericLiteral");
+                        assert_eq!(otherwise.get_type(), "Assignment");
+                    },
+                    node => panic!("\nExpected an else if, found {}\n", node.get_type())
                 }
-            }
+            },
+            node => panic!("\nExpected an if with an otherwise, found {}\n", node.get_type())
+        }
+        assert!(!parser.error_handler.has_errors());
+    }
+
+    #[test]
+    fn if_needs_comma() {
+        let mut parser = Parser::new("If true 1.");
+        assert!(parser.parse_statement().is_none());
+        assert_eq!(parser.error_handler.errors(), &[Error::new(ErrorType::MissingToken, 1, 9).with_span(8, 9).with_expected(&[TokenType::Comma])]);
+    }
+
+    fn eval_bool(text: &str) -> bool {
+        let mut parser = Parser::new(text);
+        match parser.parse_e().expect("\nExpected an expression\n").eval() {
+            Ok(LiteralValue::Boolean(val)) => val,
+            Ok(val) => panic!("\n'{}' evaluated to {} instead of a boolean\n", text, val),
+            Err(e) => panic!("\nFailed to evaluate '{}': {}\n", text, e)
+        }
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(eval_bool("1 + 1 is 2"));
+        assert!(eval_bool("1 is not equal to 2"));
+        assert!(eval_bool("3 is less than 4"));
+        assert!(!eval_bool("4 is less than 4"));
+        assert!(eval_bool("4 is less than or equal to 4"));
+        assert!(eval_bool("5 is greater than 4"));
+        assert!(!eval_bool("3 is greater than or equal to 4"));
+        assert!(eval_bool("true is true"));
+        assert!(eval_bool("\"Hi\" is \"Hi\""));
+        assert!(eval_bool(r#""She said \"hi\"" is “She said "hi"”"#));
+        assert!(eval_bool(r#""Two\nlines" is "Two
+lines""#));
+        assert!(!eval_bool("1 is none"));
+        assert!(eval_bool("none is none"));
+    }
+
+    #[test]
+    fn logic() {
+        assert!(eval_bool("true and not false"));
+        assert!(!eval_bool("true and false"));
+        assert!(eval_bool("false or true"));
+        assert!(eval_bool("not (1 is 2 or 2 is 3)"));
+    }
+
+    #[test]
+    fn and_or_short_circuit() {
+        //The right side would fail if it were evaluated
+        assert!(!eval_bool("false and 1 / 0 is 1"));
+        assert!(eval_bool("true or Undeclared"));
+    }
+
+    #[test]
+    fn mismatched_comparisons() {
+        //The span of the operator, every case is ASCII so its column is one past where it starts
+        let cases = [("1 is true", (2, 4)), ("1 is less than \"a\"", (2, 14)), ("true and 1", (5, 8)), ("not 3", (0, 3))];
+        for (text, (start, end)) in cases {
+            let mut parser = Parser::new(text);
+            let expected = Error::new(ErrorType::InvalidOperands, 1, start + 1).with_span(start, end);
+            assert_eq!(parser.parse_e().expect("\nExpected an expression\n").eval(), Err(expected),
+                "\n'{}' should have invalid operands\n", text);
+        }
     }
 }
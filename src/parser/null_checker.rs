@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use super::{error_handler::{Error, ErrorType}, tree_node::TreeNode};
+use super::LiteralValue;
+
+/*
+    Makes sure nothing that may be none gets used before it's checked.
+    A declaration ending in "?" or "‽" may be none, anything else may not.
+    Inside "If X is not none, ..." X is known not to be none.
+*/
+pub struct NullChecker<'a> {
+    //Every declared name and whether it may be none
+    nullable: HashMap<&'a str, bool>,
+    //Names that have been checked by an enclosing if
+    narrowed: Vec<&'a str>,
+    errors: Vec<Error>
+}
+
+#[allow(dead_code)]
+impl<'a> NullChecker<'a> {
+    pub fn new() -> Self {
+        NullChecker {
+            nullable: HashMap::new(),
+            narrowed: Vec::new(),
+            errors: Vec::new()
+        }
+    }
+
+    //Reports none given to a name that can't be none, and anything that may be none used without an if checking it.
+    //Whether a name may be none is kept for the chapters after the one declaring it
+    pub fn check(&mut self, statements: &[TreeNode<'a>]) -> Vec<Error> {
+        for statement in statements {
+            self.check_node(statement);
+        }
+        std::mem::take(&mut self.errors)
+    }
+
+    fn check_node(&mut self, node: &TreeNode<'a>) {
+        match node {
+            TreeNode::Declaration{identifier, nullable, val, ..} => {
+                self.check_node(val);
+                if !nullable && self.may_be_none(val) {
+                    self.errors.push(Error::at(ErrorType::NullAssignment, &val.token()));
+                }
+                if let LiteralValue::Identifier(name) = identifier {
+                    self.nullable.insert(name, *nullable);
+                }
+            },
+            TreeNode::Assignment{identifier, val, ..} => {
+                self.check_node(val);
+                if let LiteralValue::Identifier(name) = identifier {
+                    if self.nullable.get(name) == Some(&false) && self.may_be_none(val) {
+                        self.errors.push(Error::at(ErrorType::NullAssignment, &val.token()));
+                    }
+                }
+            },
+            TreeNode::If{condition, expression, otherwise, unreachable, ..} => {
+                self.check_operand(condition);
+                let depth = self.narrowed.len();
+                let checked = non_none_names(condition);
+                self.narrowed.extend(checked);
+                self.check_node(expression);
+                self.narrowed.truncate(depth);
+                for branch in otherwise.iter().map(|otherwise| otherwise.as_ref()).chain(unreachable) {
+                    self.check_node(branch);
+                }
+            },
+
+            TreeNode::Addition{left,right,..} |
+            TreeNode::Subtraction{left,right,..} |
+            TreeNode::Multiplication{left,right,..} |
+            TreeNode::Division{left,right,..} |
+            TreeNode::Modulo{left,right,..} |
+            TreeNode::Less{left,right,..} |
+            TreeNode::Greater{left,right,..} |
+            TreeNode::LessEq{left,right,..} |
+            TreeNode::GreaterEq{left,right,..} => {
+                self.check_operand(left);
+                self.check_operand(right);
+            },
+            TreeNode::And{left,right,..} |
+            TreeNode::Or{left,right,..} => {
+                self.check_operand(left);
+                self.check_operand(right);
+            },
+            TreeNode::Negation{arg,..} |
+            TreeNode::Not{arg,..} => self.check_operand(arg),
+
+            //Anything can be compared to none, that's how it's checked
+            TreeNode::EqTo{left,right,..} |
+            TreeNode::NeqTo{left,right,..} => {
+                self.check_node(left);
+                self.check_node(right);
+            },
+            TreeNode::Parens{val,..} => self.check_node(val),
+            TreeNode::Statement{expression,..} => self.check_node(expression),
+            _ => ()
+        }
+    }
+
+    //Math, logic and if conditions can't use anything that may be none
+    fn check_operand(&mut self, operand: &TreeNode<'a>) {
+        if self.may_be_none(operand) {
+            self.errors.push(Error::at(ErrorType::UncheckedNull, &operand.token()));
+        } else {
+            self.check_node(operand);
+        }
+    }
+
+    fn may_be_none(&self, node: &TreeNode<'a>) -> bool {
+        match node {
+            TreeNode::None{..} => true,
+            TreeNode::Identifier{val: LiteralValue::Identifier(name),..} => {
+                self.nullable.get(name) == Some(&true) && !self.narrowed.contains(name)
+            },
+            TreeNode::Parens{val,..} => self.may_be_none(val),
+            _ => false
+        }
+    }
+}
+
+//Names a condition proves aren't none, "X is not none and Y isn't none"
+fn non_none_names<'a>(condition: &TreeNode<'a>) -> Vec<&'a str> {
+    match condition {
+        TreeNode::NeqTo{left,right,..} => match (left.as_ref(), right.as_ref()) {
+            (TreeNode::Identifier{val: LiteralValue::Identifier(name),..}, TreeNode::None{..}) |
+            (TreeNode::None{..}, TreeNode::Identifier{val: LiteralValue::Identifier(name),..}) => vec![*name],
+            _ => Vec::new()
+        },
+        TreeNode::And{left,right,..} => {
+            let mut names = non_none_names(left);
+            names.extend(non_none_names(right));
+            names
+        },
+        TreeNode::Parens{val,..} => non_none_names(val),
+        _ => Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NullChecker,Error,ErrorType};
+    use super::super::Parser;
+
+    fn check(text: &str) -> Vec<Error> {
+        let mut parser = Parser::new(text);
+        let program = parser.parse().unwrap();
+        NullChecker::new().check(program.statements())
+    }
+
+    #[test]
+    fn nullable_declarations() {
+        assert_eq!(check("There is a number called X? There is a number called Y, it is none‽"), []);
+        assert_eq!(check("There is a number called X, it is 5? X + 1."),
+            [Error::new(ErrorType::UncheckedNull, 1, 38).with_span(37, 38)]);
+    }
+
+    #[test]
+    fn non_nullable_rejects_none() {
+        assert_eq!(check("There is a number called X, it is none."),
+            [Error::new(ErrorType::NullAssignment, 1, 35).with_span(34, 38)]);
+        assert_eq!(check("There is a number called X!"),
+            [Error::new(ErrorType::NullAssignment, 1, 26).with_span(25, 26)]);
+        assert_eq!(check("There is a number called X? There is a number called Y, it is X."),
+            [Error::new(ErrorType::NullAssignment, 1, 63).with_span(62, 63)]);
+        assert_eq!(check("There is a number called X, it is 1. X, it is none."),
+            [Error::new(ErrorType::NullAssignment, 1, 47).with_span(46, 50)]);
+    }
+
+    #[test]
+    fn checked_before_use() {
+        assert_eq!(check("There is a number called X? If X is not none, X * 2."), []);
+        assert_eq!(check("There is a number called X? If X isn't none and 1 is 1, -X."), []);
+        assert_eq!(check("There is a number called X? If X is none, X * 2."),
+            [Error::new(ErrorType::UncheckedNull, 1, 43).with_span(42, 43)]);
+        assert_eq!(check("There is a number called X? If X is not none, 1. X * 2."),
+            [Error::new(ErrorType::UncheckedNull, 1, 50).with_span(49, 50)]);
+    }
+
+    #[test]
+    fn conditions_and_logic() {
+        assert_eq!(check("There is a boolean called B? If B, 1."),
+            [Error::new(ErrorType::UncheckedNull, 1, 33).with_span(32, 33)]);
+        assert_eq!(check("There is a boolean called B? B and true. B or true. not B."), [
+            Error::new(ErrorType::UncheckedNull, 1, 30).with_span(29, 30),
+            Error::new(ErrorType::UncheckedNull, 1, 42).with_span(41, 42),
+            Error::new(ErrorType::UncheckedNull, 1, 57).with_span(56, 57)
+        ]);
+        assert_eq!(check("There is a boolean called B? If B is not none, B and not B; otherwise true or false."), []);
+    }
+
+    #[test]
+    fn otherwise_is_not_narrowed() {
+        assert_eq!(check("There is a number called X? If X is not none, X * 2; otherwise X + 1."),
+            [Error::new(ErrorType::UncheckedNull, 1, 64).with_span(63, 64)]);
+    }
+}
@@ -0,0 +1,42 @@
+use super::{tokenizer::Token, tree_node::TreeNode};
+
+//Every statement in a file, in the order they were written
+pub struct Program<'a> {
+    statements: Vec<TreeNode<'a>>,
+    //Holds whatever comes after the last statement as trivia
+    eof: Token<'a>
+}
+
+impl<'a> Program<'a> {
+    pub fn new(statements: Vec<TreeNode<'a>>, eof: Token<'a>) -> Self {
+        Program {
+            statements,
+            eof
+        }
+    }
+
+    pub fn statements(&self) -> &[TreeNode<'a>] {
+        &self.statements
+    }
+
+    //The text the program was parsed from, only all of it if it was parsed with Parser::lossless
+    #[allow(dead_code)]
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for tkn in self.statements.iter().flat_map(|statement| statement.tokens()) {
+            text.push_str(tkn.trivia());
+            text.push_str(tkn.raw());
+        }
+        text.push_str(self.eof.trivia());
+        text
+    }
+}
+
+impl std::fmt::Display for Program<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for statement in &self.statements {
+            writeln!(f,"{}",statement)?;
+        }
+        Ok(())
+    }
+}
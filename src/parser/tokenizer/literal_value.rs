@@ -20,7 +20,6 @@ impl<'a> LiteralValue<'a> {
         LiteralValue::String(str)
     }
 
-    #[allow(dead_code)]
     pub fn new_bool(bool: &bool) -> Self {
         LiteralValue::Boolean(*bool)
     }
@@ -46,6 +45,33 @@ impl<'a> LiteralValue<'a> {
     }
 }
 
+/*
+    Turns the escapes in a string literal into the characters they stand for:
+    \" \“ \” \' \\ \n \t
+    Any other backslash is kept as it is.
+*/
+pub fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(escaped @ ('"' | '“' | '”' | '\'' | '\\')) => unescaped.push(escaped),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            },
+            None => unescaped.push('\\')
+        }
+    }
+    unescaped
+}
+
 impl std::fmt::Display for LiteralValue<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f,"{}",
@@ -62,3 +88,16 @@ impl std::fmt::Display for LiteralValue<'_> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::unescape;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(unescape(r#"She said \"hi\"."#), "She said \"hi\".");
+        assert_eq!(unescape(r"\“Quoted\” \\ \' \n\t"), "“Quoted” \\ ' \n\t");
+        assert_eq!(unescape(r"C:\path\"), "C:\\path\\");
+        assert_eq!(unescape("no escapes"), "no escapes");
+    }
+}
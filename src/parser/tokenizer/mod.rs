@@ -1,610 +1,742 @@
-use regex::Regex;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use regex::{Regex, RegexSet};
 
 mod token;
 pub use token::{Token,TokenType};
 
+mod number_words;
+#[allow(unused_imports)]
+pub use number_words::to_words;
+
+mod vocabulary;
+pub use vocabulary::Vocabulary;
+
 mod literal_value;
-pub use literal_value::LiteralValue;
+pub use literal_value::{LiteralValue,unescape};
 
-use super::error_handler::{Error,ErrorType};
+use super::error_handler::{Error,ErrorType,LineIndex};
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct Tokenizer<'a> {
     text: &'a str,
     current: usize,
-    line: u32,
-    start: usize,
-    token: Result<Token<'a>,Error>,
-    lines: Vec<u32>,
+    //Tokens that have been read but not scanned yet, never empty
+    lookahead: VecDeque<Result<Token<'a>,Error>>,
+    //Shared so cloning the tokenizer to look ahead stays cheap
+    lines: Rc<LineIndex<'a>>,
+    lexer: &'a Lexer,
+    //Whether the next token starts a sentence, at the start of the text or after an EOS
+    sentence_start: bool,
+    lossless: bool,
+    //Where the last token scanned ends
+    trivia_start: usize,
 }
 impl<'a> Tokenizer<'a> {
 
+    //Reads text with the standard vocabulary
+    #[allow(dead_code)]
     pub fn new(text: &'a str) -> Tokenizer<'a> {
-        let tkn: Token<'a> = get_first_token(text,0,0);
-        Tokenizer {
+        Self::with_lexer(text, Lexer::standard())
+    }
+
+    pub fn with_lexer(text: &'a str, lexer: &'a Lexer) -> Tokenizer<'a> {
+        Self::with_options(text, lexer, false)
+    }
+
+    /*
+        Keeps every byte of text, each token's trivia is everything between it and the token before,
+        whitespace, asides and anything that couldn't be read.
+        The trivia and raw text of every token up to and including EOF add up to the whole text.
+    */
+    #[allow(dead_code)]
+    pub fn lossless(text: &'a str, lexer: &'a Lexer) -> Tokenizer<'a> {
+        Self::with_options(text, lexer, true)
+    }
+
+    fn with_options(text: &'a str, lexer: &'a Lexer, lossless: bool) -> Tokenizer<'a> {
+        let mut tokenizer = Tokenizer {
+            lexer,
+            lossless,
+            trivia_start: 0,
+            sentence_start: true,
             text,
-            current: tkn.len(),
-            line: 1,
-            start: 0,
-            token: match tkn.token_type {
-                TokenType::Invalid => {
-                    Err(Error::new(
-                        ErrorType::MissingToken,
-                        0,
-                        0
-                    ))
-                },
-                _ => Ok(tkn)
-            },
-            lines: Vec::new(),
-        }
+            current: 0,
+            lookahead: VecDeque::new(),
+            lines: Rc::new(LineIndex::new(text)),
+        };
+        let first = tokenizer.get_next_token();
+        tokenizer.lookahead.push_back(first);
+        tokenizer
     }
 
     /********************************************
     *Returns next token and moves current position
-    *Advances self.token
+    *Advances the lookahead buffer
     *********************************************/
-    pub fn scan(&mut self) -> Result<Token<'a>,Error> {
-        let result: Result<Token<'a>,Error>;
-        (result, self.token) = (self.token, self.get_next_token(),);
+    pub fn scan_token(&mut self) -> Result<Token<'a>,Error> {
+        let result = self.lookahead.pop_front().expect("Lookahead buffer is empty!!!");
+        if self.lookahead.is_empty() {
+            let next = self.get_next_token();
+            self.lookahead.push_back(next);
+        }
         result
     }
 
     //Returns a reference to the current token
-    pub fn peek(&self) -> &Result<Token,Error> {
-        &self.token
+    pub fn peek(&self) -> &Result<Token<'a>,Error> {
+        &self.lookahead[0]
+    }
+
+    //Returns a reference to the token n places after the current one, peek_nth(0) is peek()
+    //Past the end of the text this is always EOF
+    pub fn peek_nth(&mut self, n: usize) -> &Result<Token<'a>,Error> {
+        while self.lookahead.len() <= n {
+            let next = self.get_next_token();
+            self.lookahead.push_back(next);
+        }
+        &self.lookahead[n]
+    }
+
+    //Where every line of the text starts
+    pub fn lines(&self) -> Rc<LineIndex<'a>> {
+        Rc::clone(&self.lines)
     }
  
     fn increment(&mut self, increase: usize) {
         self.current += increase
     }
     fn get_next_token(&mut self) -> Result<Token<'a>,Error> {
-        let cl = |func: Box<dyn FnOnce(LiteralValue, &'a str, u32, usize) -> Result<Token<'a>, Error>>, lit: LiteralValue, raw: &'a str| -> Result<Token<'a>, Error> {
-            func(lit, raw, self.line, self.current)
-        };
-        let tkn = get_first_token(
-            &self.text[self.current..],
-            Box::new(cl)
-        );
-        match tkn.token_type {
-            TokenType::Invalid => { Err(Error::new(
-                    ErrorType::MissingToken,
-                    self.line,
-                    self.current
-                ))
-            },
-            TokenType::Whitespace => {
-                self.increment(tkn.len());
-                self.get_next_token()
-            },
-            _ => {
-                self.increment(tkn.len());
-                Ok(tkn)
+        //Asides skipped on the way to the next token, kept on it as trivia
+        let mut trivia: Option<(usize, usize)> = None;
+        loop {
+            let (line, column) = self.lines.location(self.current);
+            let tkn = self.lexer.first_token(
+                &self.text[self.current..],
+                self.sentence_start,
+                line,
+                column,
+                self.current
+            );
+            let unclosed = match self.text[self.current..].chars().next() {
+                Some('"' | '“') => Some(ErrorType::UnterminatedString),
+                Some('[') => Some(ErrorType::UnterminatedComment),
+                _ => None
+            };
+            let tkn = match tkn.token_type {
+                //Dialogue or an aside that's never closed runs to the end of the text
+                TokenType::Invalid if unclosed.is_some() => {
+                    let start = self.current;
+                    self.current = self.text.len();
+                    return Err(Error::new(
+                        unclosed.expect("Checked above"),
+                        line,
+                        column
+                    ).with_span(start, self.current))
+                },
+                //Skip the word or character that couldn't be read so the next scan moves on
+                TokenType::Invalid => {
+                    let rest = &self.text[self.current..];
+                    let word = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+                    let skipped = if word > 0 { word } else { rest.chars().next().map_or(0, char::len_utf8) };
+                    let start = self.current;
+                    self.increment(skipped);
+                    return Err(Error::new(
+                        ErrorType::UnknownToken,
+                        line,
+                        column
+                    ).with_span(start, self.current))
+                },
+                TokenType::Whitespace => {
+                    self.increment(tkn.len());
+                    continue;
+                },
+                TokenType::Comment => {
+                    let start = trivia.map_or(self.current, |(start, _)| start);
+                    self.increment(tkn.len());
+                    trivia = Some((start, self.current));
+                    continue;
+                },
+                //EOF doesn't consume anything, so scanning past the end keeps returning it
+                TokenType::Eof => tkn,
+                _ => {
+                    self.increment(tkn.len());
+                    tkn
+                }
+            };
+            self.sentence_start = matches!(
+                tkn.token_type,
+                TokenType::Dot | TokenType::Bang | TokenType::Question | TokenType::Interrobang
+            );
+            if self.lossless {
+                trivia = Some((self.trivia_start, tkn.start()));
             }
+            self.trivia_start = self.current;
+            return Ok(match trivia {
+                Some((start, end)) => tkn.with_trivia(&self.text[start..end]),
+                None => tkn
+            });
         }
     }
 }
 
-fn get_first_token<'a>(
-    text: &'a str,
-    func: Box<dyn FnOnce(
-        Box<dyn FnOnce(
-            LiteralValue,
-            &'a str,
-            u32,
-            usize
-        ) -> Result<Token<'a>, Error>>, LiteralValue, &str
-    ) -> Result<Token<'a>,Error>>) -> Result<Token, Error> {
-    //match regex
-    let mut result = Token::invalid();
-    if let Some(cap) = Regex::new(
-
-    /**********************************************\
-    *                   if                         * 
-    \**********************************************/
-        r"^[iI]f"
-    ).unwrap().find(text) {
-        result =
-            func(//TODO make Token::new that takes in a TokenType, raw, line, and position and then
-                //use that in the closure instead
-                Box::new(Token::new_if),
-                LiteralValue::new_keyword(&text[..cap.len()]),
-                &text[cap.len()..],
-            )?;
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *               therefore                      * 
-    \**********************************************/
-        r"^; [tT]herefore"
-    ).unwrap().find(text) {
-        result = Token::new_therefore(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                equal to                      * 
-    \**********************************************/
-        r"^is equal to"
-    ).unwrap().find(text) {
-        result = Token::new_eq_to (
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *              not equal to                    * 
-    \**********************************************/
-        r"^(is not|isn't) equal to"
-    ).unwrap().find(text) {
-        result = Token::new_neq_to(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                   or                         * 
-    \**********************************************/
-        r"^or"
-    ).unwrap().find(text) {
-        result = Token::new_or(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                   not                        * 
-    \**********************************************/
-        r"^(is not|isn't)"
-    ).unwrap().find(text) {
-        result = Token::new_not(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                   and                        * 
-    \**********************************************/
-        r"^and"
-    ).unwrap().find(text) {
-        result = Token::new_and(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *             less or equal to                 * 
-    \**********************************************/
-        r"^is less than or equal to"
-    ).unwrap().find(text) {
-        result = Token::new_less_eq( 
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                less than                     * 
-    \**********************************************/
-        r"^is less than"
-    ).unwrap().find(text) {
-        result = Token::new_less(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *            greater or equal                  * 
-    \**********************************************/
-        r"^is greater than or equal to"
-    ).unwrap().find(text) {
-        result = Token::new_greater_eq(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                 greater                      * 
-    \**********************************************/
-        r"^is greater than"
-    ).unwrap().find(text) {
-        result = Token::new_greater(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-    
-    /**********************************************\
-    *                 false                        * 
-    \**********************************************/
-        r"^false"
-    ).unwrap().find(text) {
-        result = Token::new_false(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                  true                        * 
-    \**********************************************/
-        r"^true"
-    ).unwrap().find(text) {
-        result = Token::new_true(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                  none                        * 
-    \**********************************************/
-        r"^none"
-    ).unwrap().find(text) {
-        result = Token::new_none(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                   you                        * 
-    \**********************************************/
-        r"^You"
-    ).unwrap().find(text) {
-        result = Token::new_you(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *               assignment                     * 
-    \**********************************************/
-        r"^((it|he|she) is | they are)"
-    ).unwrap().find(text) {
-        result = Token::new_assignment(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *               declaration                    * 
-    \**********************************************/
-        r"^[tT]here is a"
-    ).unwrap().find(text) {
-        result = Token::new_declaration(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                id keyword                    * 
-    \**********************************************/
-        r"^(called|named|labelled)"
-    ).unwrap().find(text) {
-        result = Token::new_id_keyword(
-            LiteralValue::new_keyword(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-        
-    /**********************************************\
-    *                identifier                    * 
-    \**********************************************/
-        r"^[A-Z]\w+"
-    ).unwrap().find(text) {
-        result = Token::new_identifier(
-            LiteralValue::new_identifier(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                  string                      * 
-    \**********************************************/
-        r#"^"[^"]*""#
-    ).unwrap().find(text) {
-        result = Token::new_string(
-            LiteralValue::new_string(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    
-    } else if let Some(cap) = Regex::new(
-        r#"^'[^']*'"#
-    ).unwrap().find(text) {
-        result = Token::new_string(
-            LiteralValue::new_string(&text[..cap.len()]),
-                &text[..cap.len()],
-                line,
-                pos
-            ).unwrap();
-        } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                  number                      * 
-    \**********************************************/
-        r"^\d+(\.[\d]+)?"
-    ).unwrap().find(text) {
-        result = Token::new_number(
-            LiteralValue::new_number(cap.as_str().parse::<f64>().expect("Non-number matched as numeric literal!!!")),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-     /**********************************************\
-    *                   comma                      * 
-    \**********************************************/
-        r"^,"
-    ).unwrap().find(text) {
-            result = Token::new_comma(
-                LiteralValue::new_symbol(&text[..cap.len()]),
-                &text[..cap.len()],
-                line,
-                pos
-            ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                    dot                       * 
-    \**********************************************/
-        r"^\."
-    ).unwrap().find(text) {
-        result = Token::new_dot(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                   bang                       * 
-    \**********************************************/
-        r"^!"
-    ).unwrap().find(text) {
-        result = Token::new_bang(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                 question                     * 
-    \**********************************************/
-        r"^\?"
-    ).unwrap().find(text) {
-        result = Token::new_question(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                 interrobang                  * 
-    \**********************************************/
-        r"^(‽|\?!|!\?)"
-    ).unwrap().find(text) {
-        result = Token::new_interrobang(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                semicolon                     * 
-    \**********************************************/
-        r"^;"
-    ).unwrap().find(text) {
-        result = Token::new_semicolon(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                    colon                     * 
-    \**********************************************/
-        r"^:"
-    ).unwrap().find(text) {
-        result = Token::new_colon(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                left paren                    * 
-    \**********************************************/
-        r"^\("
-    ).unwrap().find(text) {
-        result = Token::new_left_paren(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    }  else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                 right paren                  * 
-    \**********************************************/
-        r"^\)"
-    ).unwrap().find(text) {
-        result = Token::new_right_paren(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                    plus                      * 
-    \**********************************************/
-        r"^\+"
-    ).unwrap().find(text) {
-        result = Token::new_plus(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                   minus                      * 
-    \**********************************************/
-        r"^-"
-    ).unwrap().find(text) {
-        result = Token::new_minus(
-        LiteralValue::new_symbol(&text[..cap.len()]),
-        &text[..cap.len()],
-        line,
-        pos
-    ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                    star                      * 
-    \**********************************************/
-        r"\*"
-    ).unwrap().find(text) {
-        result = Token::new_star(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                   slash                      * 
-    \**********************************************/
-        r"^/"
-    ).unwrap().find(text) {
-        result = Token::new_slash(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos 
-        ).unwrap();
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                 ellipsis                     * 
-    \**********************************************/
-        r"^\.\.\."
-    ).unwrap().find(text) {
-        result = Token::new_ellipsis(
-            LiteralValue::new_symbol(&text[..cap.len()]),
-            &text[..cap.len()],
-            line,
-            pos
-        ).unwrap();
-    } else if Regex::new(
-       
-    /**********************************************\
-    *                    eof                       * 
-    \**********************************************/
-        r"^\z"
-    ).unwrap().is_match(text) {
-        result = Token::new_eof(
-            LiteralValue::eof(),
-            "\0",
-            line,
-            pos
-        ).unwrap();
-     } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                  new line                    * 
-    \**********************************************/
-        r"^\s*\n"
-    ).unwrap().find(text) {
-        result = Token::new_whitespace(
-            LiteralValue::none(),
-            &text[..cap.len()],
-            line,
-            pos
-        )
-    } else if let Some(cap) = Regex::new(
-       
-    /**********************************************\
-    *                whitespace                    * 
-    \**********************************************/
-        r"^\s+"
-    ).unwrap().find(text) {
-        result = Token::new_whitespace(
-            LiteralValue::none(),
-            &text[..cap.len()],
-            line,
-            pos
-        )
-    }
-
-    result
-} 
+//Every token up to EOF, EOF itself isn't returned
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>,Error>;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.peek() {
+            Ok(tkn) if tkn.token_type == TokenType::Eof => None,
+            _ => Some(self.scan_token())
+        }
+    }
+}
+
+/*
+    Every token the lexer knows that isn't a keyword, in priority order.
+    Keywords come from a Vocabulary and are tried before all of these.
+    When more than one pattern matches, the one listed first wins.
+*/
+const RULES: &[(TokenType, &str)] = &[
+    (TokenType::Comment,        r"^\[[^\]]*\]"),
+    (TokenType::TypeKeyword,    r"^(number|string|boolean|class)\b"),
+    (TokenType::NumericLiteral, number_words::FIRST_WORDS),
+    (TokenType::Identifier,     r"^[A-Z]\w*"),
+    (TokenType::StringLiteral,  r#"^(?s)"(\\.|[^"\\])*""#),
+    (TokenType::StringLiteral,  r#"^(?s)“(\\.|[^”\\])*”"#),
+    (TokenType::NumericLiteral, r"^[0-9]+(\.[0-9]+)?"),
+    (TokenType::Comma,          r"^,"),
+    (TokenType::Ellipsis,       r"^\.\.\."),
+    (TokenType::Dot,            r"^\."),
+    (TokenType::Interrobang,    r"^(‽|\?!|!\?)"),
+    (TokenType::Bang,           r"^!"),
+    (TokenType::Question,       r"^\?"),
+    (TokenType::Semicolon,      r"^;"),
+    (TokenType::Colon,          r"^:"),
+    (TokenType::LeftParen,      r"^\("),
+    (TokenType::RightParen,     r"^\)"),
+    (TokenType::Plus,           r"^\+"),
+    (TokenType::Minus,          r"^-"),
+    (TokenType::Star,           r"^\*"),
+    (TokenType::Slash,          r"^/"),
+    (TokenType::Mod,            r"^%"),
+    (TokenType::Eof,            r"^\z"),
+    (TokenType::Whitespace,     r"^\s+"),
+];
+
+//Every pattern of a vocabulary and RULES compiled together, so a token is found in one pass over the text
+pub struct Lexer {
+    //Each pattern and whether it can only start a sentence
+    rules: Vec<(TokenType, String, bool)>,
+    set: RegexSet,
+    patterns: Vec<Regex>
+}
+
+#[allow(dead_code)]
+impl Lexer {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        //Longer phrases first so "is less than or equal to" wins over "is less than" and "is"
+        let mut keywords: Vec<&(TokenType, String)> = vocabulary.aliases().iter().collect();
+        keywords.sort_by_key(|(_, phrase)| std::cmp::Reverse(phrase.chars().count()));
+        //A keyword starting a sentence may be capitalized, "If" as well as "if"
+        let keywords = keywords.into_iter().flat_map(|(token_type, phrase)| {
+            let capitalized = capitalize(phrase).map(|capital| (*token_type, phrase_pattern(&capital), true));
+            std::iter::once((*token_type, phrase_pattern(phrase), false)).chain(capitalized)
+        });
+        let rules: Vec<(TokenType, String, bool)> = keywords
+            .chain(RULES.iter().map(|(token_type, pattern)| (*token_type, pattern.to_string(), false)))
+            .collect();
+        let sources = rules.iter().map(|(_, pattern, _)| pattern.as_str());
+        Lexer {
+            set: RegexSet::new(sources.clone()).expect("Invalid token pattern!!!"),
+            patterns: sources.map(|pattern| Regex::new(pattern).expect("Invalid token pattern!!!")).collect(),
+            rules
+        }
+    }
+
+    //The lexer for the standard vocabulary, compiled the first time it's needed and shared from then on
+    pub fn standard() -> &'static Lexer {
+        static LEXER: OnceLock<Lexer> = OnceLock::new();
+        LEXER.get_or_init(|| Lexer::new(&Vocabulary::standard()))
+    }
+
+    fn first_token<'a>(&self, text: &'a str, sentence_start: bool, line: u32, column: usize, pos: usize) -> Token<'a> {
+        for index in self.set.matches(text).iter() {
+            let (token_type, pattern, starts_sentence) = &self.rules[index];
+            let token_type = *token_type;
+            //Anywhere else a capitalized word is an identifier
+            let capitalized = text.starts_with(|c: char| c.is_uppercase());
+            if *starts_sentence && !sentence_start {
+                continue;
+            }
+            //Number words are only a number if the words around them make one, otherwise try the next rule
+            if pattern == number_words::FIRST_WORDS {
+                if capitalized && !sentence_start {
+                    continue;
+                }
+                let Some((num, len)) = number_words::parse(text) else {
+                    continue;
+                };
+                return Token::new(token_type, LiteralValue::new_number(num), &text[..len], line, column, pos);
+            }
+            let len = self.patterns[index].find(text).map_or(0, |cap| cap.len());
+            let raw = &text[..len];
+            return Token::new(token_type, literal_value(token_type, raw), raw, line, column, pos);
+        }
+        Token::invalid()
+    }
+}
+
+/*
+    Matches phrase only as whole words, "or" but not the start of "order",
+    with any whitespace between its words, including line breaks
+*/
+fn phrase_pattern(phrase: &str) -> String {
+    let words: Vec<String> = phrase.split_whitespace().map(regex::escape).collect();
+    let boundary = if phrase.ends_with(|c: char| c.is_alphanumeric() || c == '_') { r"\b" } else { "" };
+    format!("^{}{}", words.join(r"\s+"), boundary)
+}
+
+//The phrase with its first letter capitalized, None if it doesn't start with a lowercase letter
+fn capitalize(phrase: &str) -> Option<String> {
+    let mut chars = phrase.chars();
+    let first = chars.next().filter(|c| c.is_lowercase())?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+//The value a token of token_type carries for the text it matched
+fn literal_value(token_type: TokenType, raw: &str) -> LiteralValue<'_> {
+    match token_type {
+        TokenType::Identifier => LiteralValue::new_identifier(raw),
+        TokenType::StringLiteral => LiteralValue::new_string(unquote(raw)),
+        TokenType::NumericLiteral => LiteralValue::new_number(
+            raw.parse::<f64>().expect("Non-number matched as numeric literal!!!")
+        ),
+        TokenType::Comma |
+        TokenType::Dot |
+        TokenType::Bang |
+        TokenType::Question |
+        TokenType::Interrobang |
+        TokenType::Semicolon |
+        TokenType::Colon |
+        TokenType::LeftParen |
+        TokenType::RightParen |
+        TokenType::Plus |
+        TokenType::Minus |
+        TokenType::Star |
+        TokenType::Slash |
+        TokenType::Mod |
+        TokenType::Ellipsis => LiteralValue::new_symbol(raw),
+        TokenType::Eof => LiteralValue::eof(),
+        TokenType::Whitespace => LiteralValue::none(),
+        _ => LiteralValue::new_keyword(raw)
+    }
+}
+
+//The text between a string's quotes, escapes are left as written
+fn unquote(raw: &str) -> &str {
+    let mut chars = raw.chars();
+    chars.next();
+    chars.next_back();
+    chars.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tokenizer,TokenType,LiteralValue,Error,ErrorType,Lexer,Vocabulary};
+    use std::time::Instant;
+
+    fn token_types(text: &str) -> Vec<TokenType> {
+        Tokenizer::new(text)
+            .map(|tkn| tkn.expect("Failed to tokenize").token_type)
+            .collect()
+    }
 
+    #[test]
+    fn longest_phrase_wins() {
+        assert!(token_types("is less than or equal to") == [TokenType::LessEq]);
+        assert!(token_types("is less than") == [TokenType::Less]);
+        assert!(token_types("is not equal to") == [TokenType::NeqTo]);
+        assert!(token_types("is not") == [TokenType::NeqTo]);
+        assert!(token_types("not") == [TokenType::Not]);
+        assert!(token_types("is") == [TokenType::EqTo]);
+        assert!(token_types("?! ‽ ? ! ... .") == [
+            TokenType::Interrobang,
+            TokenType::Interrobang,
+            TokenType::Question,
+            TokenType::Bang,
+            TokenType::Ellipsis,
+            TokenType::Dot
+        ]);
+    }
+
+    #[test]
+    fn readme_declaration() {
+        assert!(token_types("There is a number called Example, it is 20.") == [
+            TokenType::Declaration,
+            TokenType::TypeKeyword,
+            TokenType::IdKeyword,
+            TokenType::Identifier,
+            TokenType::Comma,
+            TokenType::Assignment,
+            TokenType::NumericLiteral,
+            TokenType::Dot
+        ]);
+    }
+
+    #[test]
+    fn literal_values() {
+        let mut tokenizer = Tokenizer::new("Example 2.5 + and");
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Identifier("Example"));
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Number(2.5));
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Symbol("+"));
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Keyword("and"));
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Eof);
+    }
+
+    #[test]
+    fn tokens_know_their_line_and_column() {
+        let positions: Vec<(u32, usize, usize)> = Tokenizer::new("There is a number called X‽\n  X, it is\n\t12.")
+            .map(|tkn| tkn.expect("Failed to tokenize"))
+            .map(|tkn| (tkn.line(), tkn.column(), tkn.start()))
+            .collect();
+        assert_eq!(positions, [
+            (1, 1, 0), (1, 12, 11), (1, 19, 18), (1, 26, 25), (1, 27, 26),
+            (2, 3, 32), (2, 4, 33), (2, 6, 35),
+            (3, 2, 42), (3, 4, 44)
+        ]);
+    }
+
+    #[test]
+    fn unknown_token_position() {
+        let mut tokenizer = Tokenizer::new("1 +\n  2 # 3");
+        for _ in 0..3 {
+            assert!(tokenizer.scan_token().is_ok());
+        }
+        assert_eq!(tokenizer.scan_token(), Err(Error::new(ErrorType::UnknownToken, 2, 5).with_span(8, 9)));
+    }
+
+    #[test]
+    fn only_ascii_digits_are_numbers() {
+        //An Arabic-Indic three, f64 can't parse it so it isn't a numeric literal
+        let mut tokenizer = Tokenizer::new("X, it is ٣.");
+        assert_eq!(tokenizer.nth(3).unwrap().map_err(|error| error.error_type), Err(ErrorType::UnknownToken));
+    }
+
+    #[test]
+    fn peek_nth_looks_ahead_without_scanning() {
+        let mut tokenizer = Tokenizer::new("X is not equal to 3.");
+        assert_eq!(tokenizer.peek_nth(4).unwrap().token_type, TokenType::Eof);
+        assert_eq!(tokenizer.peek_nth(1).unwrap().token_type, TokenType::NeqTo);
+        assert_eq!(tokenizer.peek().unwrap().token_type, TokenType::Identifier);
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::Identifier);
+        assert_eq!(tokenizer.peek_nth(2).unwrap().token_type, TokenType::Dot);
+        assert_eq!(tokenizer.peek_nth(10).unwrap().token_type, TokenType::Eof);
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::NeqTo);
+    }
+
+    #[test]
+    fn iterator_moves_past_unknown_tokens() {
+        let results: Vec<Result<TokenType,Error>> = Tokenizer::new("1 # 2")
+            .map(|tkn| tkn.map(|tkn| tkn.token_type))
+            .collect();
+        assert_eq!(results, [
+            Ok(TokenType::NumericLiteral),
+            Err(Error::new(ErrorType::UnknownToken, 1, 3).with_span(2, 3)),
+            Ok(TokenType::NumericLiteral)
+        ]);
+    }
+
+    #[test]
+    fn dialogue() {
+        let mut tokenizer = Tokenizer::new(r#""Hello," “It's \“late\”.” "a \"quoted\" \\ word""#);
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::String("Hello,"));
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::String(r"It's \“late\”."));
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::String(r#"a \"quoted\" \\ word"#));
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::Eof);
+    }
+
+    #[test]
+    fn apostrophes_are_not_quotes() {
+        //A stray apostrophe is one unknown character, it doesn't swallow the text up to the next one
+        let mut tokenizer = Tokenizer::new("X's 1. Y's 2.");
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::Identifier);
+        assert_eq!(tokenizer.scan_token(), Err(Error::new(ErrorType::UnknownToken, 1, 2).with_span(1, 2)));
+        assert_eq!(tokenizer.scan_token(), Err(Error::new(ErrorType::UnknownToken, 1, 3).with_span(2, 3)));
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Number(1.0));
+    }
+
+    #[test]
+    fn dialogue_across_lines() {
+        let mut tokenizer = Tokenizer::new("\"First line\nsecond line\" X");
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!(tkn.val, LiteralValue::String("First line\nsecond line"));
+        assert_eq!((tkn.line(), tkn.column()), (1, 1));
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!((tkn.line(), tkn.column()), (2, 14));
+    }
+
+    #[test]
+    fn unterminated_dialogue() {
+        let mut tokenizer = Tokenizer::new("1.\n  \"Where are you going,\nX.");
+        tokenizer.nth(1);
+        assert_eq!(tokenizer.scan_token(), Err(Error::new(ErrorType::UnterminatedString, 2, 3).with_span(5, 29)));
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::Eof);
+
+        let results: Vec<_> = Tokenizer::new("“Escaped end\\”").collect();
+        assert_eq!(results, [Err(Error::new(ErrorType::UnterminatedString, 1, 1).with_span(0, 18))]);
+    }
+
+    #[test]
+    fn number_words() {
+        let mut tokenizer = Tokenizer::new("twenty-three is three hundred and five and a dozen? one half.");
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Number(23.0));
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::EqTo);
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!((tkn.val, tkn.len()), (LiteralValue::Number(305.0), 22));
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::And);
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Number(12.0));
+        assert_eq!(tokenizer.scan_token().unwrap().token_type, TokenType::Question);
+        assert_eq!(tokenizer.scan_token().unwrap().val, LiteralValue::Number(0.5));
+        let mut tokenizer = Tokenizer::new("X, it is minus four.");
+        assert_eq!(tokenizer.nth(3).unwrap().unwrap().val, LiteralValue::Number(-4.0));
+        assert!(token_types("There is a number called X, it is a dozen.") == [
+            TokenType::Declaration,
+            TokenType::TypeKeyword,
+            TokenType::IdKeyword,
+            TokenType::Identifier,
+            TokenType::Comma,
+            TokenType::Assignment,
+            TokenType::NumericLiteral,
+            TokenType::Dot
+        ]);
+    }
+
+    #[test]
+    fn asides_are_skipped() {
+        assert!(token_types("[Author's note: the hero.] X [Footnote:\nagain] + [a][b] 1 [end]") == [
+            TokenType::Identifier,
+            TokenType::Plus,
+            TokenType::NumericLiteral
+        ]);
+    }
+
+    #[test]
+    fn asides_are_kept_as_trivia() {
+        let mut tokenizer = Tokenizer::new("[Author's note: the hero.] X + [a]\n [b] 1 [The end]");
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!(tkn.trivia(), "[Author's note: the hero.]");
+        assert_eq!(tkn.comments().collect::<Vec<_>>(), ["Author's note: the hero."]);
+        assert_eq!(tokenizer.scan_token().unwrap().trivia(), "");
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!(tkn.trivia(), "[a]\n [b]");
+        assert_eq!(tkn.comments().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(tokenizer.scan_token().unwrap().comments().collect::<Vec<_>>(), ["The end"]);
+    }
+
+    #[test]
+    fn unterminated_aside() {
+        let results: Vec<_> = Tokenizer::new("X [Author's note: X + 1.").collect();
+        assert_eq!(results[1], Err(Error::new(ErrorType::UnterminatedComment, 1, 3).with_span(2, 24)));
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn vocabulary_adds_phrases() {
+        let mut vocabulary = Vocabulary::standard();
+        vocabulary.extend_from_json(r#"{ "Assignment": ["it becomes"], "LessEq": ["is at most"] }"#).unwrap();
+        let lexer = Lexer::new(&vocabulary);
+        let types: Vec<TokenType> = Tokenizer::with_lexer("X, it becomes 1. X is at most 2 equals true.", &lexer)
+            .map(|tkn| tkn.expect("Failed to tokenize").token_type)
+            .collect();
+        assert!(types == [
+            TokenType::Identifier,
+            TokenType::Comma,
+            TokenType::Assignment,
+            TokenType::NumericLiteral,
+            TokenType::Dot,
+            TokenType::Identifier,
+            TokenType::LessEq,
+            TokenType::NumericLiteral,
+            TokenType::EqTo,
+            TokenType::True,
+            TokenType::Dot
+        ]);
+        assert!(token_types("X is the same as 2") == [TokenType::Identifier, TokenType::EqTo, TokenType::NumericLiteral]);
+    }
+
+    #[test]
+    fn capitalized_at_sentence_start() {
+        assert!(token_types("If X, 1. Otherwise! True? None‽ You. There is a") == [
+            TokenType::If,
+            TokenType::Identifier,
+            TokenType::Comma,
+            TokenType::NumericLiteral,
+            TokenType::Dot,
+            TokenType::Otherwise,
+            TokenType::Bang,
+            TokenType::True,
+            TokenType::Question,
+            TokenType::None,
+            TokenType::Interrobang,
+            TokenType::You,
+            TokenType::Dot,
+            TokenType::Declaration
+        ]);
+        assert!(token_types("[Aside] Twenty-three is a dozen. A dozen") == [
+            TokenType::NumericLiteral,
+            TokenType::EqTo,
+            TokenType::NumericLiteral,
+            TokenType::Dot,
+            TokenType::NumericLiteral
+        ]);
+    }
+
+    #[test]
+    fn capitalized_mid_sentence_is_identifier() {
+        assert!(token_types("X, If; Otherwise True and you, Twenty") == [
+            TokenType::Identifier,
+            TokenType::Comma,
+            TokenType::Identifier,
+            TokenType::Semicolon,
+            TokenType::Identifier,
+            TokenType::Identifier,
+            TokenType::And,
+            TokenType::You,
+            TokenType::Comma,
+            TokenType::Identifier
+        ]);
+    }
+
+    #[test]
+    fn keywords_are_whole_words() {
+        assert!(token_types("Ifrit. Youth. Truth. Nonesuch. Otherwiseland") == [
+            TokenType::Identifier,
+            TokenType::Dot,
+            TokenType::Identifier,
+            TokenType::Dot,
+            TokenType::Identifier,
+            TokenType::Dot,
+            TokenType::Identifier,
+            TokenType::Dot,
+            TokenType::Identifier
+        ]);
+        let results: Vec<Result<TokenType,Error>> = Tokenizer::new("1 order 2 android island nothing")
+            .map(|tkn| tkn.map(|tkn| tkn.token_type))
+            .collect();
+        assert_eq!(results, [
+            Ok(TokenType::NumericLiteral),
+            Err(Error::new(ErrorType::UnknownToken, 1, 3).with_span(2, 7)),
+            Ok(TokenType::NumericLiteral),
+            Err(Error::new(ErrorType::UnknownToken, 1, 11).with_span(10, 17)),
+            Err(Error::new(ErrorType::UnknownToken, 1, 19).with_span(18, 24)),
+            Err(Error::new(ErrorType::UnknownToken, 1, 26).with_span(25, 32))
+        ]);
+    }
+
+    #[test]
+    fn prefixes_still_split_on_punctuation() {
+        assert!(token_types("X or, Y and. none! isn't?") == [
+            TokenType::Identifier,
+            TokenType::Or,
+            TokenType::Comma,
+            TokenType::Identifier,
+            TokenType::And,
+            TokenType::Dot,
+            TokenType::None,
+            TokenType::Bang,
+            TokenType::NeqTo,
+            TokenType::Question
+        ]);
+    }
+
+    #[test]
+    fn phrases_span_any_whitespace() {
+        let mut tokenizer = Tokenizer::new("There  is\n a number called X, it\tis 1. X is\n    less than\r\nor equal to 2.");
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!((tkn.token_type, tkn.len()), (TokenType::Declaration, 12));
+        let types: Vec<TokenType> = tokenizer.map(|tkn| tkn.unwrap().token_type).collect();
+        assert!(types == [
+            TokenType::TypeKeyword,
+            TokenType::IdKeyword,
+            TokenType::Identifier,
+            TokenType::Comma,
+            TokenType::Assignment,
+            TokenType::NumericLiteral,
+            TokenType::Dot,
+            TokenType::Identifier,
+            TokenType::LessEq,
+            TokenType::NumericLiteral,
+            TokenType::Dot
+        ]);
+    }
+
+    //Everything a lossless tokenizer kept, in order
+    fn reprint(text: &str) -> String {
+        let mut tokenizer = Tokenizer::lossless(text, Lexer::standard());
+        let mut printed = String::new();
+        loop {
+            if let Ok(tkn) = tokenizer.scan_token() {
+                printed.push_str(tkn.trivia());
+                printed.push_str(tkn.raw());
+                if tkn.token_type == TokenType::Eof {
+                    return printed;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lossless_keeps_every_byte() {
+        let texts = [
+            "",
+            "  \n\t ",
+            "There is a number called X, it is 1.",
+            "[Author's note: twice]\r\n  There  is\n a number called Gold, it is twenty-three‽ \n\n[fin]\n",
+            "If X is not none, X, it is “Hi”; otherwise (1 + 2) * 3. [aside] [another]",
+            "1 # order $ 2 \"unterminated\n dialogue"
+        ];
+        for text in texts {
+            assert_eq!(reprint(text), text, "\nLossless tokens should reprint '{}'\n", text);
+        }
+    }
+
+    #[test]
+    fn lossless_trivia() {
+        let mut tokenizer = Tokenizer::lossless(" [a] X\n+ 1 ", Lexer::standard());
+        assert_eq!(tokenizer.scan_token().unwrap().trivia(), " [a] ");
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!((tkn.trivia(), tkn.raw()), ("\n", "+"));
+        assert_eq!(tokenizer.scan_token().unwrap().trivia(), " ");
+        let tkn = tokenizer.scan_token().unwrap();
+        assert_eq!((tkn.token_type, tkn.trivia()), (TokenType::Eof, " "));
+        assert_eq!(Tokenizer::lossless("[a] X", Lexer::standard()).scan_token().unwrap().comments().collect::<Vec<_>>(), ["a"]);
+    }
+
+    //cargo test --release tokenizer_throughput -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn tokenizer_throughput() {
+        let sentence = "There is a number called Example, it is 20 * (3 + 4)! \
+            If Example is greater than or equal to 100, Example, it is Example - 1; otherwise Example, it is 0.";
+        //Blocks are written on one continuous line, so a single long line is the usual case
+        let cases = [("one sentence a line", format!("{}\n", sentence)), ("a single line", format!("{} ", sentence))];
+        for (name, sentence) in cases {
+            let text = sentence.repeat(4 * 1024 * 1024 / sentence.len());
+            let start = Instant::now();
+            let count = token_types(&text).len();
+            let seconds = start.elapsed().as_secs_f64();
+            println!(
+                "\nTokenized {:.1} MB written as {} into {} tokens in {:.3}s ({:.1} MB/s)\n",
+                text.len() as f64 / 1_000_000.0,
+                name,
+                count,
+                seconds,
+                text.len() as f64 / 1_000_000.0 / seconds
+            );
+        }
+    }
+}
@@ -0,0 +1,354 @@
+/*
+    Numbers written out in English, "twenty-three", "three hundred and five",
+    "one half", "a dozen", "two point five", "minus four".
+    Words are separated by whitespace, or a hyphen between tens and units.
+    Only the first letter of the first word may be capitalized, "Twenty-three".
+*/
+
+const UNITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const TEENS: [&str; 10] = [
+    "ten", "eleven", "twelve", "thirteen", "fourteen",
+    "fifteen", "sixteen", "seventeen", "eighteen", "nineteen"
+];
+const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+//Largest first, each group of three digits is named by one of these
+const SCALES: [(&str, u64); 3] = [("billion", 1_000_000_000), ("million", 1_000_000), ("thousand", 1_000)];
+const FRACTIONS: [(&str, f64); 6] = [
+    ("half", 2.0), ("halves", 2.0),
+    ("third", 3.0), ("thirds", 3.0),
+    ("quarter", 4.0), ("quarters", 4.0)
+];
+
+//Every word a number can start with
+pub const FIRST_WORDS: &str = r"^(?i:a|minus|zero|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve|thirteen|fourteen|fifteen|sixteen|seventeen|eighteen|nineteen|twenty|thirty|forty|fifty|sixty|seventy|eighty|ninety)\b";
+
+//A position in the words of a number, the number starts at 0
+#[derive(Clone,Copy)]
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize
+}
+
+impl<'a> Cursor<'a> {
+    //The next word and the cursor after it
+    fn take(self) -> Option<(&'a str, Cursor<'a>)> {
+        let mut rest = &self.text[self.pos..];
+        let mut pos = self.pos;
+        if pos > 0 {
+            let gap = if rest.starts_with('-') { 1 } else { rest.len() - rest.trim_start().len() };
+            if gap == 0 {
+                return None;
+            }
+            rest = &rest[gap..];
+            pos += gap;
+        }
+        let capital = pos == 0 && rest.starts_with(|c: char| c.is_ascii_uppercase());
+        let len = rest
+            .char_indices()
+            .find(|(i, c)| !(c.is_ascii_lowercase() || (capital && *i == 0)))
+            .map_or(rest.len(), |(i, _)| i);
+        if len == 0 || rest[len..].starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        Some((&rest[..len], Cursor { text: self.text, pos: pos + len }))
+    }
+
+    //The cursor after the next word, if it's expected
+    fn expect(self, expected: &str) -> Option<Cursor<'a>> {
+        match self.take() {
+            Some((word, next)) if word.eq_ignore_ascii_case(expected) => Some(next),
+            _ => None
+        }
+    }
+}
+
+fn position(words: &[&str], word: &str) -> Option<u64> {
+    words.iter().position(|w| w.eq_ignore_ascii_case(word)).map(|i| i as u64)
+}
+
+/*
+    Parse Small: 1 to 99
+    S -> unit | teen | tens [unit]
+*/
+fn parse_small(cursor: Cursor<'_>) -> Option<(u64, Cursor<'_>)> {
+    let (word, next) = cursor.take()?;
+    if let Some(tens) = position(&TENS[2..], word) {
+        let tens = (tens + 2) * 10;
+        return match next.take() {
+            Some((unit, after)) if position(&UNITS[1..], unit).is_some() => {
+                Some((tens + position(&UNITS, unit)?, after))
+            },
+            _ => Some((tens, next))
+        };
+    }
+    if let Some(teen) = position(&TEENS, word) {
+        return Some((teen + 10, next));
+    }
+    position(&UNITS[1..], word).map(|unit| (unit + 1, next))
+}
+
+//A small number, or "a" which counts as one but can't stand alone
+fn parse_count(cursor: Cursor<'_>) -> Option<(u64, Cursor<'_>, bool)> {
+    match cursor.expect("a") {
+        Some(next) => Some((1, next, true)),
+        None => parse_small(cursor).map(|(num, next)| (num, next, false))
+    }
+}
+
+/*
+    Parse Hundreds: 1 to 999
+    H -> C hundred [[and] S] | S
+*/
+fn parse_hundreds(cursor: Cursor<'_>) -> Option<(u64, Cursor<'_>, bool)> {
+    let (count, next, is_a) = parse_count(cursor)?;
+    let Some(after) = next.expect("hundred") else {
+        return Some((count, next, is_a));
+    };
+    let hundreds = count * 100;
+    let rest = after.expect("and").unwrap_or(after);
+    match parse_small(rest) {
+        Some((small, end)) => Some((hundreds + small, end, false)),
+        None => Some((hundreds, after, false))
+    }
+}
+
+/*
+    Parse Whole: any number of groups, each named by a smaller scale than the last
+    W -> H [scale [[and] W]]
+*/
+fn parse_whole(cursor: Cursor<'_>) -> Option<(u64, Cursor<'_>)> {
+    let mut total = 0;
+    let mut cursor = cursor;
+    let mut largest = u64::MAX;
+    let mut first = true;
+    loop {
+        let start = if first { cursor } else { cursor.expect("and").unwrap_or(cursor) };
+        let Some((num, next, is_a)) = parse_hundreds(start) else {
+            return (!first).then_some((total, cursor));
+        };
+        let scale = next.take().and_then(|(word, after)| {
+            SCALES.iter().find(|(name, size)| name.eq_ignore_ascii_case(word) && *size < largest).map(|(_, size)| (*size, after))
+        });
+        match scale {
+            Some((size, after)) => {
+                total += num * size;
+                largest = size;
+                cursor = after;
+                first = false;
+            },
+            //"a" has to be followed by a scale, "a thousand" is a number but "a" isn't
+            None if is_a => return (!first).then_some((total, cursor)),
+            None => return Some((total + num, next))
+        }
+    }
+}
+
+/*
+    Parse Fraction:
+    F -> C fraction
+*/
+fn parse_fraction(cursor: Cursor<'_>) -> Option<(f64, Cursor<'_>)> {
+    let (count, next, _) = parse_count(cursor)?;
+    let (word, after) = next.take()?;
+    let (_, denominator) = FRACTIONS.iter().find(|(name, _)| name.eq_ignore_ascii_case(word))?;
+    Some((count as f64 / denominator, after))
+}
+
+/*
+    Parse Number: the longest of
+    N -> zero | F | C dozen | W [and F] | W point digit+
+*/
+fn parse_number(cursor: Cursor<'_>) -> Option<(f64, Cursor<'_>)> {
+    let mut candidates = Vec::new();
+    candidates.extend(parse_fraction(cursor));
+    if let Some((count, next, _)) = parse_count(cursor) {
+        if let Some(after) = next.expect("dozen") {
+            candidates.push((count as f64 * 12.0, after));
+        }
+    }
+    let whole = cursor.expect("zero").map(|next| (0, next)).or_else(|| parse_whole(cursor));
+    if let Some((whole, next)) = whole {
+        candidates.push((whole as f64, next));
+        if let Some((fraction, after)) = next.expect("and").and_then(parse_fraction) {
+            candidates.push((whole as f64 + fraction, after));
+        }
+        if let Some(mut after) = next.expect("point") {
+            let mut digits = String::new();
+            while let Some((digit, end)) = after.take().and_then(|(word, end)| Some((position(&UNITS, word)?, end))) {
+                digits.push_str(&digit.to_string());
+                after = end;
+            }
+            if !digits.is_empty() {
+                candidates.push((format!("{}.{}", whole, digits).parse().ok()?, after));
+            }
+        }
+    }
+    candidates.into_iter().max_by_key(|(_, end)| end.pos)
+}
+
+/*
+    Parse Signed:
+    SN -> [minus] N
+*/
+fn parse_signed(cursor: Cursor<'_>) -> Option<(f64, Cursor<'_>)> {
+    match cursor.expect("minus") {
+        Some(next) => parse_number(next).map(|(num, end)| (-num, end)),
+        None => parse_number(cursor)
+    }
+}
+
+//The value of the number words at the start of text and how many bytes they take up
+pub fn parse(text: &str) -> Option<(f64, usize)> {
+    parse_signed(Cursor { text, pos: 0 }).map(|(num, end)| (num, end.pos))
+}
+
+fn small_words(num: u64) -> String {
+    match num {
+        0..=9 => UNITS[num as usize].to_string(),
+        10..=19 => TEENS[num as usize - 10].to_string(),
+        _ if num.is_multiple_of(10) => TENS[num as usize / 10].to_string(),
+        _ => format!("{}-{}", TENS[num as usize / 10], UNITS[num as usize % 10])
+    }
+}
+
+fn hundreds_words(num: u64) -> String {
+    match (num / 100, num % 100) {
+        (0, small) => small_words(small),
+        (hundreds, 0) => format!("{} hundred", UNITS[hundreds as usize]),
+        (hundreds, small) => format!("{} hundred and {}", UNITS[hundreds as usize], small_words(small))
+    }
+}
+
+fn whole_words(num: u64) -> String {
+    if num == 0 {
+        return UNITS[0].to_string();
+    }
+    let mut groups = Vec::new();
+    let mut rest = num;
+    for (name, size) in SCALES {
+        if rest >= size {
+            groups.push(format!("{} {}", hundreds_words(rest / size), name));
+            rest %= size;
+        }
+    }
+    match rest {
+        0 => (),
+        //"one thousand and five"
+        1..=99 if !groups.is_empty() => groups.push(format!("and {}", small_words(rest))),
+        _ => groups.push(hundreds_words(rest))
+    }
+    groups.join(" ")
+}
+
+/*
+    Writes num out in words so that parse reads back the same number.
+    Halves and quarters are written as fractions, anything else after the point digit by digit.
+    Numbers too big to have a name are left as digits.
+*/
+#[allow(dead_code)]
+pub fn to_words(num: f64) -> String {
+    if num < 0.0 {
+        return format!("minus {}", to_words(-num));
+    }
+    if !num.is_finite() || num >= 1_000_000_000_000.0 {
+        return num.to_string();
+    }
+    let whole = num.trunc();
+    let fraction = match num - whole {
+        0.0 => return whole_words(whole as u64),
+        0.5 => Some("a half"),
+        0.25 => Some("a quarter"),
+        0.75 => Some("three quarters"),
+        _ => None
+    };
+    match fraction {
+        Some(fraction) if whole == 0.0 => fraction.replacen("a ", "one ", 1),
+        Some(fraction) => format!("{} and {}", whole_words(whole as u64), fraction),
+        None => {
+            let digits = num.to_string();
+            let (_, decimals) = digits.split_once('.').expect("Fraction without a decimal point!!!");
+            let decimals: Vec<&str> = decimals.bytes().map(|digit| UNITS[(digit - b'0') as usize]).collect();
+            format!("{} point {}", whole_words(whole as u64), decimals.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, to_words};
+
+    fn value(text: &str) -> Option<f64> {
+        parse(text).filter(|(_, len)| *len == text.len()).map(|(num, _)| num)
+    }
+
+    #[test]
+    fn whole_numbers() {
+        let cases = [
+            ("zero", 0.0), ("seven", 7.0), ("twelve", 12.0), ("twenty", 20.0), ("twenty-three", 23.0),
+            ("ninety nine", 99.0), ("a hundred", 100.0), ("three hundred and five", 305.0),
+            ("three hundred five", 305.0), ("twelve hundred", 1200.0), ("a thousand", 1000.0),
+            ("one thousand and one", 1001.0), ("two million three hundred thousand and forty-two", 2_300_042.0),
+            ("four billion", 4_000_000_000.0)
+        ];
+        for (text, expected) in cases {
+            assert_eq!(value(text), Some(expected), "\n'{}' should be {}\n", text, expected);
+        }
+    }
+
+    #[test]
+    fn fractions_and_dozens() {
+        let cases = [
+            ("one half", 0.5), ("a half", 0.5), ("three quarters", 0.75), ("two thirds", 2.0 / 3.0),
+            ("one and a half", 1.5), ("ten and three quarters", 10.75), ("a dozen", 12.0),
+            ("two dozen", 24.0), ("three point two five", 3.25), ("zero", 0.0),
+            ("minus four", -4.0), ("Minus one and a half", -1.5)
+        ];
+        for (text, expected) in cases {
+            assert_eq!(value(text), Some(expected), "\n'{}' should be {}\n", text, expected);
+        }
+    }
+
+    #[test]
+    fn stops_at_the_longest_number() {
+        assert_eq!(parse("three and true"), Some((3.0, 5)));
+        assert_eq!(parse("five hundred and X"), Some((500.0, 12)));
+        assert_eq!(parse("one thousand million"), Some((1000.0, 12)));
+        assert_eq!(parse("two three"), Some((2.0, 3)));
+        assert_eq!(parse("twenty - three"), Some((20.0, 6)));
+        assert_eq!(parse("one."), Some((1.0, 3)));
+        assert_eq!(parse("a"), None);
+        assert_eq!(parse("a number"), None);
+        assert_eq!(parse("oneself"), None);
+        assert_eq!(parse("tenth"), None);
+        assert_eq!(parse("Twenty-three"), Some((23.0, 12)));
+        assert_eq!(parse("A dozen"), Some((12.0, 7)));
+        assert_eq!(parse("TWenty"), None);
+        assert_eq!(parse("twenty-Three"), Some((20.0, 6)));
+        assert_eq!(parse("minus"), None);
+        assert_eq!(parse("minus minus four"), None);
+    }
+
+    #[test]
+    fn words() {
+        assert_eq!(to_words(0.0), "zero");
+        assert_eq!(to_words(23.0), "twenty-three");
+        assert_eq!(to_words(305.0), "three hundred and five");
+        assert_eq!(to_words(1001.0), "one thousand and one");
+        assert_eq!(to_words(2_300_042.0), "two million three hundred thousand and forty-two");
+        assert_eq!(to_words(0.5), "one half");
+        assert_eq!(to_words(10.75), "ten and three quarters");
+        assert_eq!(to_words(2.72), "two point seven two");
+        assert_eq!(to_words(-4.0), "minus four");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut nums: Vec<f64> = (0..2000).map(f64::from).collect();
+        nums.extend([0.25, 7.5, 99.75, 0.1, 12.625, 1_000_000.0, 987_654_321_012.0, 40_000_019.0]);
+        nums.extend([-1.0, -0.5, -42.0, -12.625, -1_000_001.0]);
+        for num in nums {
+            let words = to_words(num);
+            assert_eq!(value(&words), Some(num), "\n{} was written as '{}'\n", num, words);
+        }
+    }
+}
@@ -1,71 +1,5 @@
 use core::fmt;
-use super::{LiteralValue,Error,ErrorType};
-
-
-macro_rules! token_constructor {
-    (Symbol, $type: tt, $sym: expr, $name: ident) => {
-        pub fn $name(val: LiteralValue<'a>, raw: &'a str, line: u32, start: usize) -> Result<Token<'a>,Error> {
-            if let LiteralValue::Symbol(sym) = &val {
-                if *sym != $sym {
-                    return Err(Error::new(
-                        ErrorType::InvalidTokenValue,
-                        line,
-                        start
-                    ))
-                }
-                Ok(Token {
-                    token_type: TokenType::$type,
-                    val,
-                    raw,
-                    line,
-                    start
-                })
-            } else {
-                Err(Error::new(ErrorType::InvalidTokenValue, line, start))
-            }
-        }
-        
-    };
-
-    (Keyword, $type: tt, $name: ident, $($lit: literal);+) => {
-        pub fn $name(val: LiteralValue<'a>, raw: &'a str, line: u32, start: usize) -> Result<Token<'a>,Error> {
-            if let LiteralValue::Keyword(key) = &val {
-                if $(*key != $lit)||+ {
-                    return Err(Error::new(
-                        ErrorType::InvalidTokenValue,
-                        line,
-                        start
-                    ))
-                }
-                Ok(Token {
-                    token_type: TokenType::$type,
-                    val,
-                    raw,
-                    line,
-                    start
-                })
-            } else {
-                Err(Error::new(ErrorType::InvalidTokenValue, line, start))
-            }
-        }
-    };
-
-    ($lit_type: tt, $type: tt, $name: ident) => {
-        pub fn $name(val: LiteralValue<'a>, raw: &'a str, line: u32, start: usize) -> Result<Token<'a>,Error> {
-            if let LiteralValue::$lit_type(_) = &val {
-                Ok(Token {
-                    token_type: TokenType::$type,
-                    val,
-                    raw,
-                    line,
-                    start
-                })
-            } else {
-                Err( Error::new( ErrorType::InvalidTokenValue, line, start ))
-            }
-        }
-    }
-}
+use super::LiteralValue;
 
 #[allow(dead_code)]
 #[derive(PartialEq,Eq,Clone,Hash,Copy)]
@@ -86,16 +20,16 @@ pub enum TokenType {
     Ellipsis,
 
     //Control Flow
-    If, Therefore, 
+    If, Therefore, Otherwise,
     
     //Comparison
     EqTo, NeqTo,Or, Not, And, Less, Greater, LessEq, GreaterEq,
     
     //Keywords
     False, True, None, You,
-    Assignment, Declaration, IdKeyword,
+    Assignment, Declaration, IdKeyword, TypeKeyword,
 
-    Whitespace, Eof, Invalid, Empty, NewLine
+    Whitespace, Comment, Eof, Invalid, Empty, NewLine
 }
 
 impl fmt::Display for TokenType {
@@ -121,6 +55,7 @@ impl fmt::Display for TokenType {
             TokenType::Ellipsis => "...".to_string(),
             TokenType::If => "If".to_string(),
             TokenType::Therefore => "Therefore".to_string(),
+            TokenType::Otherwise => "Otherwise".to_string(),
             TokenType::And => "And".to_string(),
             TokenType::Or => "Or".to_string(),
             TokenType::Not => "Not".to_string(),
@@ -135,24 +70,53 @@ impl fmt::Display for TokenType {
             TokenType::You => "You".to_string(),
             TokenType::None => "None".to_string(),
             TokenType::IdKeyword => "Id Keyword".to_string(),
+            TokenType::TypeKeyword => "Type Keyword".to_string(),
             TokenType::Declaration => "Declaration".to_string(),
             TokenType::Assignment => "Assignment".to_string(),
             TokenType::Eof => "EOF".to_string(),
             TokenType::Empty => "Empty".to_string(),
             TokenType::Whitespace => "Whitespace".to_string(),
+            TokenType::Comment => "Comment".to_string(),
             TokenType::Invalid => "Error".to_string(),
             TokenType::NewLine => "New Line".to_string(),
         })
     }
 }
 
+impl TokenType {
+    //How a token is written in the standard vocabulary, for saying it's missing
+    pub fn description(&self) -> String {
+        match self {
+            TokenType::Identifier => "a name".to_string(),
+            TokenType::TypeKeyword => "a type, like `number`".to_string(),
+            TokenType::IdKeyword => "`called`".to_string(),
+            TokenType::Assignment => "`it is`".to_string(),
+            TokenType::If => "`if`".to_string(),
+            TokenType::Otherwise => "`otherwise`".to_string(),
+            _ => format!("`{}`", self)
+        }
+    }
+}
+
+impl fmt::Debug for TokenType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{}",self)
+    }
+}
+
 #[derive(PartialEq,Clone,Copy)]
 pub struct Token<'a> {
     pub token_type: TokenType,
     pub val: LiteralValue<'a>,
     raw: &'a str,
     line: u32,
-    start: usize
+    //Characters from the start of the line, starting at 1
+    column: usize,
+    //Byte offset into the whole text
+    start: usize,
+    //Asides written before the token, from the start of the first to the end of the last.
+    //In lossless mode everything between the token and the one before
+    trivia: &'a str
 }
 
 impl fmt::Display for Token<'_> {
@@ -162,9 +126,10 @@ impl fmt::Display for Token<'_> {
                 \"val\": {},
                 \"raw\": {},
                 \"line\": {},
+                \"column\": {},
                 \"start\": {}
             }}
-        ", &self.token_type, &self.val, &self.raw, self.line, self.start)
+        ", &self.token_type, &self.val, &self.raw, self.line, self.column, self.start)
     }
 }
 
@@ -185,130 +150,60 @@ impl<'a> Token<'a> {
         self.line
     }
 
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
     pub fn start(&self) -> usize {
         self.start
     }
 
-    pub fn new_whitespace(val: LiteralValue<'a>, raw: &'a str, line: u32, start: usize) -> Token<'a> {
+    pub fn raw(&self) -> &'a str {
+        self.raw
+    }
+
+    pub fn trivia(&self) -> &'a str {
+        self.trivia
+    }
+
+    //The text of every aside in the trivia, without brackets
+    pub fn comments(&self) -> impl Iterator<Item = &'a str> {
+        self.trivia
+            .split('[')
+            .skip(1)
+            .filter_map(|aside| aside.split_once(']').map(|(comment, _)| comment))
+    }
+
+    pub fn with_trivia(self, trivia: &'a str) -> Token<'a> {
+        Token {
+            trivia,
+            ..self
+        }
+    }
+
+    pub fn new(token_type: TokenType, val: LiteralValue<'a>, raw: &'a str, line: u32, column: usize, start: usize) -> Token<'a> {
         Token {
-            token_type: TokenType::Whitespace,
+            token_type,
             val,
             raw,
             line,
-            start
+            column,
+            start,
+            trivia: ""
         }
     }
 
-    pub fn new_eof(val: LiteralValue<'a>, raw: &'a str, line: u32, start: usize) -> Result<Token<'a>,Error> {
-        if let LiteralValue::Eof = &val {
-            Ok(Token {
-                token_type: TokenType::Eof,
-                val,
-                raw,
-                line,
-                start
-            })
-        } else {
-            Err( Error::new( ErrorType::InvalidTokenValue, line, start ))
-        }
-    }
-    
     pub fn invalid() -> Self {
         Token {
             token_type: TokenType::Invalid,
             val: LiteralValue::none(),
             raw: "\0",
             line: 0,
-            start: 0
+            column: 0,
+            start: 0,
+            trivia: ""
         }
     }
-    pub fn from(ty: TokenType, lit: LiteralValue<'a>, line: u32, pos: usize) -> Result<Token<'a>,Error> {
-        match &ty {
-            NumericLiteral
-            StringLiteral
-            Identifier
-            Comma
-            Dot
-            Bang
-            Question
-            Interrobang
-            Semicolon
-            Colon
-            LeftParen
-            RightParen
-            Plus
-            Minus
-            Slash
-            Star
-            Mod
-            Ellipsis
-            If
-            Therefore 
-            EqTo
-            NeqTo
-            Or
-            Not
-            And
-            Less
-            Greater
-            LessEq
-            GreaterEq
-            False
-            True
-            None
-            You
-            Assignment
-            Declaration
-            IdKeyword
-            Whitespace
-            Eof
-            Invalid
-            Empty
-            NewLine        
-        }
-    }
-    //                 LiteralType     TokenType    Function Name 
-    token_constructor!(Identifier,     Identifier, new_identifier);
-    token_constructor!(    String,  StringLiteral,     new_string); 
-    token_constructor!(    Number, NumericLiteral,     new_number);
- 
-    //              LiteralType TokenType  Char      Function Name
-    token_constructor!(Symbol,      Comma,  ",",       new_comma);
-    token_constructor!(Symbol,        Dot,  ".",         new_dot);
-    token_constructor!(Symbol,       Bang,  "!",        new_bang);
-    token_constructor!(Symbol,   Question,  "?",    new_question);
-    token_constructor!(Symbol,  Semicolon,  ";",   new_semicolon); 
-    token_constructor!(Symbol,      Colon,  ":",       new_colon); 
-    token_constructor!(Symbol,  LeftParen,  "(",  new_left_paren);
-    token_constructor!(Symbol, RightParen,  ")", new_right_paren); 
-    token_constructor!(Symbol,       Plus,  "+",        new_plus);
-    token_constructor!(Symbol,      Minus,  "-",       new_minus);
-    token_constructor!(Symbol,      Star,   "*",        new_star);
-    token_constructor!(Symbol,     Slash,   "/",       new_slash);
-    token_constructor!(Symbol,       Mod,   "%",         new_mod);
-    token_constructor!(Symbol,  Ellipsis, "...",    new_ellipsis);
-
-    //                LiteralType  TokenType   Function Name   Match(es)
-    token_constructor!(Keyword,         And,         new_and, "and"); 
-    token_constructor!(Keyword,          Or,          new_or, "or"); 
-    token_constructor!(Keyword,        EqTo,       new_eq_to, "is equal to"); 
-    token_constructor!(Keyword,        Less,        new_less, "is less than");
-    token_constructor!(Keyword,      LessEq,     new_less_eq, "is less than or equal to"); 
-    token_constructor!(Keyword,     Greater,     new_greater, "is greater than");
-    token_constructor!(Keyword,   GreaterEq,  new_greater_eq, "is greater than or equal to");
-    token_constructor!(Keyword,        True,        new_true, "true");
-    token_constructor!(Keyword,       False,       new_false, "false");
-    token_constructor!(Keyword,        None,        new_none, "none");
-    token_constructor!(Keyword,         You,         new_you, "You"); 
-    token_constructor!(Keyword,         Not,         new_not, "is not";"isn't"); 
-    token_constructor!(Keyword,       NeqTo,      new_neq_to, "is not equal to" ; "isn't equal to"); 
-    token_constructor!(Keyword,          If,          new_if, "If" ; "if");
-    token_constructor!(Keyword,   Therefore,   new_therefore, "Therefore" ; "therefore");
-    token_constructor!(Keyword, Declaration, new_declaration, "; Therefore" ; "; therefore");
-    token_constructor!(Keyword,   IdKeyword,  new_id_keyword, "called";"named";"labelled");
-    token_constructor!(Keyword, Interrobang, new_interrobang, "!?";"?!";"‽");
-    token_constructor!(Keyword,  Assignment,  new_assignment, "it is";"he is";"she is";"they are");
-
 }
 
 impl std::fmt::Debug for Token<'_> {
@@ -318,11 +213,12 @@ impl std::fmt::Debug for Token<'_> {
             \"val\": {},
             \"raw\": {},
             \"line\": {},
+            \"column\": {},
             \"start\": {},
             \"len\": {},
             \"end\": {}
         }}
-    ",&self.token_type,&self.val,&self.raw,&self.line,&self.start,&self.len(),&self.end())
+    ",&self.token_type,&self.val,&self.raw,&self.line,&self.column,&self.start,&self.len(),&self.end())
     }
 }
 #[cfg(test)]
@@ -353,6 +249,7 @@ mod tests {
         assert_eq!(TokenType::Ellipsis.to_string(), "...");
         assert_eq!(TokenType::If.to_string(), "If");
         assert_eq!(TokenType::Therefore.to_string(), "Therefore");
+        assert_eq!(TokenType::Otherwise.to_string(), "Otherwise");
         assert_eq!(TokenType::And.to_string(), "And");
         assert_eq!(TokenType::Or.to_string(), "Or");
         assert_eq!(TokenType::Not.to_string(), "Not");
@@ -367,6 +264,7 @@ mod tests {
         assert_eq!(TokenType::You.to_string(), "You");
         assert_eq!(TokenType::None.to_string(), "None");
         assert_eq!(TokenType::IdKeyword.to_string(), "Id Keyword");
+        assert_eq!(TokenType::TypeKeyword.to_string(), "Type Keyword");
         assert_eq!(TokenType::Declaration.to_string(), "Declaration");
         assert_eq!(TokenType::Assignment.to_string(), "Assignment");
         assert_eq!(TokenType::Eof.to_string(), "EOF");
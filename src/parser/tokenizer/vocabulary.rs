@@ -0,0 +1,125 @@
+use super::TokenType;
+
+//The phrases every story understands, see vocabulary.json
+const STANDARD: &str = include_str!("vocabulary.json");
+
+/*
+    Every phrase the lexer reads as a keyword, and the keyword it stands for.
+    Phrases are kept in lowercase, the lexer also accepts them capitalized at the start of a sentence.
+    Extra phrases are read from JSON, an object mapping keyword names to lists of phrases:
+    { "EqTo": ["is the same as", "equals"], "Assignment": ["it becomes"] }
+*/
+#[derive(Clone)]
+pub struct Vocabulary {
+    aliases: Vec<(TokenType, String)>
+}
+
+#[allow(dead_code)]
+impl Vocabulary {
+    pub fn new() -> Self {
+        Vocabulary {
+            aliases: Vec::new()
+        }
+    }
+
+    //The phrases in vocabulary.json
+    pub fn standard() -> Self {
+        Self::from_json(STANDARD).expect("Invalid standard vocabulary!!!")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let mut vocabulary = Self::new();
+        vocabulary.extend_from_json(text)?;
+        Ok(vocabulary)
+    }
+
+    //Adds every phrase in text, nothing is added if any of it is invalid
+    pub fn extend_from_json(&mut self, text: &str) -> Result<(), String> {
+        let value = json::parse(text).map_err(|e| e.to_string())?;
+        if !value.is_object() {
+            return Err("Vocabulary must be an object of keyword names to phrases".to_string());
+        }
+        let mut aliases = Vec::new();
+        for (name, phrases) in value.entries() {
+            let token_type = keyword_type(name).ok_or(format!("'{}' isn't a keyword that can have phrases", name))?;
+            if !phrases.is_array() {
+                return Err(format!("The phrases for '{}' must be a list", name));
+            }
+            for phrase in phrases.members() {
+                match phrase.as_str() {
+                    Some(phrase) if !phrase.trim().is_empty() => aliases.push((token_type, phrase.to_lowercase())),
+                    _ => return Err(format!("Every phrase for '{}' must be non-empty text", name))
+                }
+            }
+        }
+        self.aliases.extend(aliases);
+        Ok(())
+    }
+
+    pub fn add(&mut self, token_type: TokenType, phrase: &str) {
+        self.aliases.push((token_type, phrase.to_lowercase()));
+    }
+
+    pub fn aliases(&self) -> &[(TokenType, String)] {
+        &self.aliases
+    }
+}
+
+//The token types that are written as phrases, by their names in the vocabulary
+fn keyword_type(name: &str) -> Option<TokenType> {
+    Some(match name {
+        "If" => TokenType::If,
+        "Therefore" => TokenType::Therefore,
+        "Otherwise" => TokenType::Otherwise,
+        "And" => TokenType::And,
+        "Or" => TokenType::Or,
+        "Not" => TokenType::Not,
+        "EqTo" => TokenType::EqTo,
+        "NeqTo" => TokenType::NeqTo,
+        "Less" => TokenType::Less,
+        "LessEq" => TokenType::LessEq,
+        "Greater" => TokenType::Greater,
+        "GreaterEq" => TokenType::GreaterEq,
+        "True" => TokenType::True,
+        "False" => TokenType::False,
+        "None" => TokenType::None,
+        "You" => TokenType::You,
+        "Assignment" => TokenType::Assignment,
+        "Declaration" => TokenType::Declaration,
+        "IdKeyword" => TokenType::IdKeyword,
+        _ => return None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Vocabulary,TokenType};
+
+    #[test]
+    fn standard_vocabulary() {
+        let vocabulary = Vocabulary::standard();
+        assert!(vocabulary.aliases().contains(&(TokenType::EqTo, "is the same as".to_string())));
+        assert!(vocabulary.aliases().contains(&(TokenType::Declaration, "there is a".to_string())));
+    }
+
+    #[test]
+    fn extend_from_json() {
+        let mut vocabulary = Vocabulary::new();
+        assert_eq!(vocabulary.extend_from_json(r#"{ "Assignment": ["It becomes"], "Or": ["or else"] }"#), Ok(()));
+        assert_eq!(vocabulary.aliases(), [
+            (TokenType::Assignment, "it becomes".to_string()),
+            (TokenType::Or, "or else".to_string())
+        ]);
+    }
+
+    #[test]
+    fn invalid_json() {
+        let mut vocabulary = Vocabulary::new();
+        assert!(vocabulary.extend_from_json("[\"is\"]").is_err());
+        assert!(vocabulary.extend_from_json(r#"{ "Plus": ["added to"] }"#).is_err());
+        assert!(vocabulary.extend_from_json(r#"{ "Or": "or else" }"#).is_err());
+        assert!(vocabulary.extend_from_json(r#"{ "Or": ["or else", ""] }"#).is_err());
+        assert!(vocabulary.extend_from_json("{ \"Or\": [").is_err());
+        assert!(vocabulary.aliases().is_empty());
+    }
+}
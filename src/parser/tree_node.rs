@@ -1,5 +1,6 @@
-use super::{error_handler::{Error, ErrorType}, tokenizer::{Token,TokenType}};
+use super::{error_handler::{Error, ErrorType}, tokenizer::{Token,TokenType,unescape}};
 use super::LiteralValue;
+use super::interpreter::Environment;
 
 macro_rules! node_constructor {
     (UNARY, $type: tt, $name: ident) => {
@@ -21,6 +22,64 @@ macro_rules! node_constructor {
     }
 }
 
+//The type word of a declaration, "There is a number called..."
+#[derive(PartialEq,Eq,Clone,Copy,Debug)]
+pub enum ValueType {
+    Number,
+    String,
+    Boolean,
+    Class
+}
+
+impl ValueType {
+    pub fn from_keyword(key: &str) -> Option<Self> {
+        match key {
+            "number" => Some(ValueType::Number),
+            "string" => Some(ValueType::String),
+            "boolean" => Some(ValueType::Boolean),
+            "class" => Some(ValueType::Class),
+            _ => None
+        }
+    }
+}
+
+impl std::fmt::Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{}",match &self {
+            ValueType::Number => "number",
+            ValueType::String => "string",
+            ValueType::Boolean => "boolean",
+            ValueType::Class => "class",
+        })
+    }
+}
+
+//Whether other chapters can see a declaration, "." and "?" are private, "!" and "‽" are public
+#[derive(PartialEq,Eq,Clone,Copy,Debug)]
+pub enum Visibility {
+    Private,
+    Public
+}
+
+impl Visibility {
+    pub fn from_terminator(terminator: &Token) -> Self {
+        match terminator.token_type {
+            TokenType::Bang |
+            TokenType::Interrobang => Visibility::Public,
+            _ => Visibility::Private
+        }
+    }
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{}",match &self {
+            Visibility::Private => "private",
+            Visibility::Public => "public",
+        })
+    }
+}
+
 #[allow(dead_code)]
 pub enum TreeNode<'a> {
     NumericLiteral{val: LiteralValue<'a>,token: Token<'a>},
@@ -50,13 +109,15 @@ pub enum TreeNode<'a> {
     
     Ellipsis{val: LiteralValue<'a>,token: Token<'a>},
 
-    If{condition: Box<TreeNode<'a>>,expression: Box<TreeNode<'a>>,token: Token<'a>},
+    //Branches written after the otherwise are kept in unreachable, they never run.
+    //tokens are the commas, semicolons and otherwise between the branches
+    If{condition: Box<TreeNode<'a>>,expression: Box<TreeNode<'a>>,otherwise: Option<Box<TreeNode<'a>>>,unreachable: Vec<TreeNode<'a>>,token: Token<'a>,tokens: Vec<Token<'a>>},
     Therefore{condition: Box<TreeNode<'a>>,expression: Box<TreeNode<'a>>,token: Token<'a>},
     
     EqTo{left: Box<TreeNode<'a>>,right: Box<TreeNode<'a>>,token: Token<'a>},
     NeqTo{left: Box<TreeNode<'a>>,right: Box<TreeNode<'a>>,token: Token<'a>},
     Or{left: Box<TreeNode<'a>>,right: Box<TreeNode<'a>>,token: Token<'a>},
-    Not{left: Box<TreeNode<'a>>,right: Box<TreeNode<'a>>,token: Token<'a>},
+    Not{arg: Box<TreeNode<'a>>,token: Token<'a>},
     And{left: Box<TreeNode<'a>>,right: Box<TreeNode<'a>>,token: Token<'a>},
     Less{left: Box<TreeNode<'a>>,right: Box<TreeNode<'a>>,token: Token<'a>},
     Greater{left: Box<TreeNode<'a>>,right: Box<TreeNode<'a>>,token: Token<'a>},
@@ -66,8 +127,11 @@ pub enum TreeNode<'a> {
     BooleanLiteral{val: LiteralValue<'a>,token: Token<'a>},
     None{val: LiteralValue<'a>,token: Token<'a>},
     You{val: LiteralValue<'a>,token: Token<'a>},
-    Assignment{identifier: LiteralValue<'a>, val: LiteralValue<'a>,token: Token<'a>},
-    Declaration{identifier: LiteralValue<'a>, val: Box<TreeNode<'a>>,token: Token<'a>},
+    //tokens are the name and comma before "it is"
+    Assignment{identifier: LiteralValue<'a>, val: Box<TreeNode<'a>>,token: Token<'a>,tokens: Vec<Token<'a>>},
+    //tokens are the type, "called", the comma and "it is", the name is id_token
+    Declaration{identifier: LiteralValue<'a>, var_type: ValueType, visibility: Visibility, nullable: bool, val: Box<TreeNode<'a>>,token: Token<'a>,id_token: Token<'a>,tokens: Vec<Token<'a>>,terminator: Token<'a>},
+    Statement{expression: Box<TreeNode<'a>>,terminator: Token<'a>},
     
     Eof{ val: LiteralValue<'a>, token: Token<'a> },
     Empty{ val: LiteralValue<'a>, token: Token<'a> },
@@ -125,11 +189,41 @@ impl std::fmt::Display for TreeNode<'_> {
                 ",arg
                 )
             },
+            TreeNode::Not{arg,..} => {
+                format!("
+                    \"{}\" : {{
+                        \"arg\": {{
+                            {}
+                        }} 
+                    }}
+                ",self.get_type(),arg
+                )
+            },
             TreeNode::Keyword{val,..} => val.to_string(),
 
             TreeNode::Ellipsis{val,..} => val.to_string(),
 
-            TreeNode::If{condition,expression,..} |
+            TreeNode::If{condition,expression,otherwise,unreachable,..} => {
+                format!("
+                    \"{}\": {{
+                        \"condition\": {{
+                            {}
+                        }},
+                        \"expression\": {{
+                            {}
+                        }},
+                        \"otherwise\": {{
+                            {}
+                        }},
+                        \"unreachable\": [
+                            {}
+                        ]
+                    }}
+                ",self.get_type(),condition,expression,match otherwise {
+                    Some(otherwise) => otherwise.to_string(),
+                    None => "null".to_string()
+                },unreachable.iter().map(|branch| format!("{{{}}}", branch)).collect::<Vec<_>>().join(","))
+            }
             TreeNode::Therefore{condition,expression,..} => {
                 format!("
                     \"{}\": {{
@@ -146,7 +240,6 @@ impl std::fmt::Display for TreeNode<'_> {
             TreeNode::EqTo{left,right,..} |
             TreeNode::NeqTo{left,right,..} |
             TreeNode::Or{left,right,..} |
-            TreeNode::Not{left,right,..} |
             TreeNode::And{left,right,..} |
             TreeNode::Less{left,right,..} |
             TreeNode::Greater{left,right,..} |
@@ -182,14 +275,27 @@ impl std::fmt::Display for TreeNode<'_> {
                     }}
                 ",self.get_type(),identifier,val)
             },
-            TreeNode::Declaration{identifier,val,..} => {
+            TreeNode::Declaration{identifier,var_type,visibility,nullable,val,terminator,..} => {
                 format!("
                     \"{}\": {{
+                        \"type\": {},
+                        \"visibility\": {},
+                        \"nullable\": {},
                         \"{}\": {{
                             {}
-                        }}
+                        }},
+                        \"terminator\": {}
                     }}
-                ",self.get_type(),identifier,val)
+                ",self.get_type(),var_type,visibility,nullable,identifier,val,terminator.token_type)
+            },
+
+            TreeNode::Statement{expression,terminator} => {
+                format!("
+                    \"{}\": {{
+                        {},
+                        \"terminator\": {}
+                    }}
+                ",self.get_type(),expression,terminator.token_type)
             },
 
             TreeNode::Eof{..} => "EOF".to_string(),
@@ -211,11 +317,68 @@ impl<'a> TreeNode<'a> {
         }
     }
 
-    pub fn new_if(condition: TreeNode<'a>, expression: TreeNode<'a>, token: Token<'a>) -> TreeNode<'a> {
+    pub fn new_if(
+        condition: TreeNode<'a>,
+        expression: TreeNode<'a>,
+        otherwise: Option<TreeNode<'a>>,
+        unreachable: Vec<TreeNode<'a>>,
+        token: Token<'a>,
+        tokens: Vec<Token<'a>>
+    ) -> TreeNode<'a> {
         TreeNode::If {
             condition: Box::new(condition),
             expression: Box::new(expression),
-            token
+            otherwise: otherwise.map(Box::new),
+            unreachable,
+            token,
+            tokens
+        }
+    }
+
+    pub fn new_parens(val: TreeNode<'a>, left_token: Token<'a>, right_token: Token<'a>) -> TreeNode<'a> {
+        TreeNode::Parens {
+            val: Box::new(val),
+            left_token,
+            right_token
+        }
+    }
+
+    pub fn new_declaration(
+        identifier: LiteralValue<'a>,
+        var_type: ValueType,
+        val: TreeNode<'a>,
+        token: Token<'a>,
+        id_token: Token<'a>,
+        tokens: Vec<Token<'a>>,
+        terminator: Token<'a>
+    ) -> TreeNode<'a> {
+        TreeNode::Declaration {
+            identifier,
+            var_type,
+            visibility: Visibility::from_terminator(&terminator),
+            //"?" and "‽" mark a declaration that may be none
+            nullable: matches!(terminator.token_type, TokenType::Question | TokenType::Interrobang),
+            val: Box::new(val),
+            token,
+            id_token,
+            tokens,
+            terminator
+        }
+    }
+
+    pub fn new_assignment(identifier: LiteralValue<'a>, val: TreeNode<'a>, token: Token<'a>, tokens: Vec<Token<'a>>) -> TreeNode<'a> {
+        TreeNode::Assignment {
+            identifier,
+            val: Box::new(val),
+            token,
+            tokens
+        }
+    }
+
+    pub fn new_statement(expression: TreeNode<'a>, terminator: Token<'a>) -> TreeNode<'a> {
+        TreeNode::Statement {
+            expression: Box::new(expression),
+            terminator
         }
     }
 
@@ -226,11 +389,26 @@ impl<'a> TreeNode<'a> {
         }
     }
 
+    pub fn new_not(arg: TreeNode<'a>, token: Token<'a>) -> TreeNode<'a> {
+        TreeNode::Not {
+            arg: Box::new(arg),
+            token
+        }
+    }
+
     node_constructor!(BINARY, Addition, new_addition);
     node_constructor!(BINARY, Subtraction, new_subtraction);
     node_constructor!(BINARY, Multiplication, new_multiplication);
     node_constructor!(BINARY, Division, new_division);
     node_constructor!(BINARY, Modulo, new_modulus);
+    node_constructor!(BINARY, EqTo, new_eq_to);
+    node_constructor!(BINARY, NeqTo, new_neq_to);
+    node_constructor!(BINARY, Less, new_less);
+    node_constructor!(BINARY, Greater, new_greater);
+    node_constructor!(BINARY, LessEq, new_less_eq);
+    node_constructor!(BINARY, GreaterEq, new_greater_eq);
+    node_constructor!(BINARY, And, new_and);
+    node_constructor!(BINARY, Or, new_or);
     node_constructor!(UNARY, NumericLiteral, new_number);
     node_constructor!(UNARY, StringLiteral, new_string);
     node_constructor!(UNARY, Identifier, new_identifier);
@@ -248,6 +426,124 @@ impl<'a> TreeNode<'a> {
     node_constructor!(UNARY, Eof, new_eof);
     node_constructor!(UNARY, You, new_you);
 
+    //The token a node was built around, used to point errors at it
+    pub fn token(&self) -> Token<'a> {
+        match &self {
+            TreeNode::Parens{left_token,..} => *left_token,
+            TreeNode::Statement{expression,..} => expression.token(),
+
+            TreeNode::NumericLiteral{token,..} |
+            TreeNode::StringLiteral{token,..} |
+            TreeNode::Identifier{token,..} |
+            TreeNode::Comma{token,..} |
+            TreeNode::Dot{token,..} |
+            TreeNode::Bang{token,..} |
+            TreeNode::Question{token,..} |
+            TreeNode::Interrobang{token,..} |
+            TreeNode::Semicolon{token,..} |
+            TreeNode::Colon{token,..} |
+            TreeNode::Addition{token,..} |
+            TreeNode::Subtraction{token,..} |
+            TreeNode::Multiplication{token,..} |
+            TreeNode::Division{token,..} |
+            TreeNode::Modulo{token,..} |
+            TreeNode::Negation{token,..} |
+            TreeNode::Keyword{token,..} |
+            TreeNode::Ellipsis{token,..} |
+            TreeNode::If{token,..} |
+            TreeNode::Therefore{token,..} |
+            TreeNode::EqTo{token,..} |
+            TreeNode::NeqTo{token,..} |
+            TreeNode::Or{token,..} |
+            TreeNode::Not{token,..} |
+            TreeNode::And{token,..} |
+            TreeNode::Less{token,..} |
+            TreeNode::Greater{token,..} |
+            TreeNode::LessEq{token,..} |
+            TreeNode::GreaterEq{token,..} |
+            TreeNode::BooleanLiteral{token,..} |
+            TreeNode::None{token,..} |
+            TreeNode::You{token,..} |
+            TreeNode::Assignment{token,..} |
+            TreeNode::Declaration{token,..} |
+            TreeNode::Eof{token,..} |
+            TreeNode::Empty{token,..} => *token,
+        }
+    }
+
+    //Every token the node was parsed from, in the order they were written
+    pub fn tokens(&self) -> Vec<Token<'a>> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens.sort_by_key(|tkn| tkn.start());
+        //A declaration without a value has a none made from its name token
+        tokens.dedup_by_key(|tkn| tkn.start());
+        tokens
+    }
+
+    fn collect_tokens(&self, tokens: &mut Vec<Token<'a>>) {
+        match &self {
+            TreeNode::Parens{val,left_token,right_token} => {
+                tokens.extend([*left_token, *right_token]);
+                val.collect_tokens(tokens);
+            },
+            TreeNode::Statement{expression,terminator} => {
+                tokens.push(*terminator);
+                expression.collect_tokens(tokens);
+            },
+            TreeNode::If{condition,expression,otherwise,unreachable,token,tokens: between} => {
+                tokens.push(*token);
+                tokens.extend(between);
+                condition.collect_tokens(tokens);
+                expression.collect_tokens(tokens);
+                if let Some(otherwise) = otherwise {
+                    otherwise.collect_tokens(tokens);
+                }
+                for branch in unreachable {
+                    branch.collect_tokens(tokens);
+                }
+            },
+            TreeNode::Therefore{condition,expression,token} => {
+                tokens.push(*token);
+                condition.collect_tokens(tokens);
+                expression.collect_tokens(tokens);
+            },
+            TreeNode::Assignment{val,token,tokens: before,..} => {
+                tokens.push(*token);
+                tokens.extend(before);
+                val.collect_tokens(tokens);
+            },
+            TreeNode::Declaration{val,token,id_token,tokens: words,terminator,..} => {
+                tokens.extend([*token, *id_token, *terminator]);
+                tokens.extend(words);
+                val.collect_tokens(tokens);
+            },
+            TreeNode::Addition{left,right,token} |
+            TreeNode::Subtraction{left,right,token} |
+            TreeNode::Multiplication{left,right,token} |
+            TreeNode::Division{left,right,token} |
+            TreeNode::Modulo{left,right,token} |
+            TreeNode::EqTo{left,right,token} |
+            TreeNode::NeqTo{left,right,token} |
+            TreeNode::Or{left,right,token} |
+            TreeNode::And{left,right,token} |
+            TreeNode::Less{left,right,token} |
+            TreeNode::Greater{left,right,token} |
+            TreeNode::LessEq{left,right,token} |
+            TreeNode::GreaterEq{left,right,token} => {
+                tokens.push(*token);
+                left.collect_tokens(tokens);
+                right.collect_tokens(tokens);
+            },
+            TreeNode::Negation{arg,token} |
+            TreeNode::Not{arg,token} => {
+                tokens.push(*token);
+                arg.collect_tokens(tokens);
+            },
+            _ => tokens.push(self.token())
+        }
+    }
+
     pub fn get_type(&self) -> String {
         match &self {
             TreeNode::NumericLiteral{..} => "NumericLiteral".to_string(),
@@ -285,67 +581,146 @@ impl<'a> TreeNode<'a> {
             TreeNode::You{..} => "You".to_string(),
             TreeNode::Assignment{..} => "Assignment".to_string(),
             TreeNode::Declaration{..} => "Declaration".to_string(),
+            TreeNode::Statement{..} => "Statement".to_string(),
             TreeNode::Eof{..} => "EOF".to_string(),
             TreeNode::Empty{..} => "null".to_string(),
         }
     }
 
-    pub fn eval(&self) -> Result<LiteralValue,Error> {
+    pub fn eval(&self) -> Result<LiteralValue<'a>,Error> {
+        self.eval_with(&Environment::new())
+    }
+
+    //Evaluates the node, resolving identifiers against env
+    pub fn eval_with(&self, env: &Environment<'a>) -> Result<LiteralValue<'a>,Error> {
         match &self {   
             TreeNode::Addition{left,right,token} => {
-                if let (Ok(left_val),Ok(right_val)) = (&left.eval(),&right.eval()) {
-                    if let (LiteralValue::Number(left_num),LiteralValue::Number(right_num)) = (left_val,right_val) {
-                        Ok(LiteralValue::new_number(left_num + right_num))
-                    } else {
-                        Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
-                    }
-                } else {
-                    Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
-                }
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                Ok(LiteralValue::new_number(left_num + right_num))
             }
             TreeNode::Subtraction{left,right,token} => {
-                if let (Ok(left_val),Ok(right_val)) = (&left.eval(),&right.eval()) {
-                    if let (LiteralValue::Number(left_num),LiteralValue::Number(right_num)) = (left_val,right_val) {
-                        Ok(LiteralValue::new_number(left_num - right_num))
-                    } else {
-                        Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
-                    }
-                } else {
-                    Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
-                }
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                Ok(LiteralValue::new_number(left_num - right_num))
             }
             TreeNode::Multiplication{left,right,token} => {
-                if let (Ok(left_val),Ok(right_val)) = (&left.eval(),&right.eval()) {
-                    if let (LiteralValue::Number(left_num),LiteralValue::Number(right_num)) = (left_val,right_val) {
-                        Ok(LiteralValue::new_number(left_num * right_num))
-                    } else {
-                        Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
-                    }
-                } else {
-                    Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
-                }
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                Ok(LiteralValue::new_number(left_num * right_num))
             }
             TreeNode::Division {left, right,token} => {
-                if let (Ok(left_val),Ok(right_val)) = (&left.eval(),&right.eval()) {
-                    if let (LiteralValue::Number(left_num),LiteralValue::Number(right_num)) = (left_val,right_val) {
-                        if *right_num == 0.0 { return Err(Error::new(ErrorType::DivideByZero,token.line(), token.start())) }
-                        Ok(LiteralValue::new_number(left_num / right_num))
-                    } else {
-                        Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
-                    }
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                if right_num == 0.0 { return Err(Error::at(ErrorType::DivideByZero, token)) }
+                Ok(LiteralValue::new_number(left_num / right_num))
+            },
+            TreeNode::Modulo {left, right,token} => {
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                if right_num == 0.0 { return Err(Error::at(ErrorType::DivideByZero, token)) }
+                Ok(LiteralValue::new_number(left_num % right_num))
+            },
+            TreeNode::Negation {arg,token} => {
+                if let LiteralValue::Number(num) = arg.eval_with(env)? {
+                    Ok(LiteralValue::new_number(-num))
                 } else {
-                    Err(Error::new(ErrorType::InvalidOperands, token.line(), token.start()))
+                    Err(Error::at(ErrorType::InvalidOperands, token))
+                }
+            },
+            TreeNode::EqTo{left,right,token} => {
+                Ok(LiteralValue::new_bool(&eval_equal(left, right, env, token)?))
+            },
+            TreeNode::NeqTo{left,right,token} => {
+                Ok(LiteralValue::new_bool(&!eval_equal(left, right, env, token)?))
+            },
+            TreeNode::Less{left,right,token} => {
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                Ok(LiteralValue::new_bool(&(left_num < right_num)))
+            },
+            TreeNode::Greater{left,right,token} => {
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                Ok(LiteralValue::new_bool(&(left_num > right_num)))
+            },
+            TreeNode::LessEq{left,right,token} => {
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                Ok(LiteralValue::new_bool(&(left_num <= right_num)))
+            },
+            TreeNode::GreaterEq{left,right,token} => {
+                let (left_num, right_num) = eval_numbers(left, right, env, token)?;
+                Ok(LiteralValue::new_bool(&(left_num >= right_num)))
+            },
+            //The right side of and/or is only evaluated when the left side doesn't decide it
+            TreeNode::And{left,right,token} => {
+                if !eval_bool(left, env, token)? {
+                    return Ok(LiteralValue::new_bool(&false));
                 }
+                Ok(LiteralValue::new_bool(&eval_bool(right, env, token)?))
             },
-            TreeNode::NumericLiteral{val,..} => Ok(*val),
+            TreeNode::Or{left,right,token} => {
+                if eval_bool(left, env, token)? {
+                    return Ok(LiteralValue::new_bool(&true));
+                }
+                Ok(LiteralValue::new_bool(&eval_bool(right, env, token)?))
+            },
+            TreeNode::Not{arg,token} => {
+                Ok(LiteralValue::new_bool(&!eval_bool(arg, env, token)?))
+            },
+            TreeNode::Identifier{val,token} => {
+                match val {
+                    LiteralValue::Identifier(name) => env.resolve(name).map_err(
+                        |error_type| Error::at(error_type, token)
+                    ),
+                    _ => Err(Error::at(ErrorType::InvalidTokenValue, token))
+                }
+            },
+            TreeNode::Parens{val,..} => val.eval_with(env),
+            TreeNode::Statement{expression,..} => expression.eval_with(env),
+            TreeNode::NumericLiteral{val,..} |
+            TreeNode::StringLiteral{val,..} |
+            TreeNode::BooleanLiteral{val,..} => Ok(*val),
+            TreeNode::None{..} |
             TreeNode::Empty{..} => Ok(LiteralValue::none()),
-            _ => Err(Error::new(ErrorType::NotImplemented, 0, 0))
+            _ => Err(Error::at(ErrorType::NotImplemented, &self.token()))
 
         }
     }
 
 }
 
+//Values of the same type can be compared, and anything can be compared to none
+fn eval_equal<'a>(
+    left: &TreeNode<'a>,
+    right: &TreeNode<'a>,
+    env: &Environment<'a>,
+    token: &Token<'a>
+) -> Result<bool,Error> {
+    match (left.eval_with(env)?, right.eval_with(env)?) {
+        (LiteralValue::Number(left_num),LiteralValue::Number(right_num)) => Ok(left_num == right_num),
+        (LiteralValue::String(left_str),LiteralValue::String(right_str)) => Ok(unescape(left_str) == unescape(right_str)),
+        (LiteralValue::Boolean(left_bool),LiteralValue::Boolean(right_bool)) => Ok(left_bool == right_bool),
+        (LiteralValue::None,LiteralValue::None) => Ok(true),
+        (LiteralValue::None,_) | (_,LiteralValue::None) => Ok(false),
+        _ => Err(Error::at(ErrorType::InvalidOperands, token))
+    }
+}
+
+//Evaluates the operand of a logical operator, which has to be a boolean
+fn eval_bool<'a>(node: &TreeNode<'a>, env: &Environment<'a>, token: &Token<'a>) -> Result<bool,Error> {
+    match node.eval_with(env)? {
+        LiteralValue::Boolean(val) => Ok(val),
+        _ => Err(Error::at(ErrorType::InvalidOperands, token))
+    }
+}
+
+//Evaluates both sides of a math operator, both of which have to be numbers
+fn eval_numbers<'a>(
+    left: &TreeNode<'a>,
+    right: &TreeNode<'a>,
+    env: &Environment<'a>,
+    token: &Token<'a>
+) -> Result<(f64,f64),Error> {
+    match (left.eval_with(env)?, right.eval_with(env)?) {
+        (LiteralValue::Number(left_num),LiteralValue::Number(right_num)) => Ok((left_num,right_num)),
+        _ => Err(Error::at(ErrorType::InvalidOperands, token))
+    }
+}
+
 mod tests {
     use super::{TreeNode,LiteralValue,Token};
 
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use super::{error_handler::{Error, ErrorType}, tree_node::{TreeNode, ValueType}};
+use super::LiteralValue;
+
+/*
+    Works out the type of every expression before anything is evaluated.
+    A type of None means it can't be known, like none or a name that was never declared,
+    and isn't reported here since the null checker and interpreter catch those.
+*/
+pub struct TypeChecker<'a> {
+    //Every declared name and its type word
+    types: HashMap<&'a str, ValueType>,
+    errors: Vec<Error>
+}
+
+#[allow(dead_code)]
+impl<'a> TypeChecker<'a> {
+    pub fn new() -> Self {
+        TypeChecker {
+            types: HashMap::new(),
+            errors: Vec::new()
+        }
+    }
+
+    //Reports values given to a name of another type, and operands of the wrong type for their operator.
+    //Names keep the type they're declared with in the chapters after it
+    pub fn check(&mut self, statements: &[TreeNode<'a>]) -> Vec<Error> {
+        for statement in statements {
+            self.infer(statement);
+        }
+        std::mem::take(&mut self.errors)
+    }
+
+    fn infer(&mut self, node: &TreeNode<'a>) -> Option<ValueType> {
+        match node {
+            TreeNode::NumericLiteral{..} => Some(ValueType::Number),
+            TreeNode::StringLiteral{..} => Some(ValueType::String),
+            TreeNode::BooleanLiteral{..} => Some(ValueType::Boolean),
+            TreeNode::Identifier{val: LiteralValue::Identifier(name),..} => self.types.get(name).copied(),
+
+            TreeNode::Declaration{identifier, var_type, val, ..} => {
+                self.expect(val, *var_type, ErrorType::TypeMismatch);
+                if let LiteralValue::Identifier(name) = identifier {
+                    self.types.insert(name, *var_type);
+                }
+                None
+            },
+            TreeNode::Assignment{identifier: LiteralValue::Identifier(name), val, ..} => {
+                match self.types.get(name).copied() {
+                    Some(var_type) => self.expect(val, var_type, ErrorType::TypeMismatch),
+                    None => { self.infer(val); }
+                }
+                None
+            },
+            TreeNode::If{condition, expression, otherwise, unreachable, ..} => {
+                self.expect(condition, ValueType::Boolean, ErrorType::TypeMismatch);
+                self.infer(expression);
+                for branch in otherwise.iter().map(|otherwise| otherwise.as_ref()).chain(unreachable) {
+                    self.infer(branch);
+                }
+                None
+            },
+
+            TreeNode::Addition{left,right,..} |
+            TreeNode::Subtraction{left,right,..} |
+            TreeNode::Multiplication{left,right,..} |
+            TreeNode::Division{left,right,..} |
+            TreeNode::Modulo{left,right,..} => {
+                self.expect(left, ValueType::Number, ErrorType::InvalidOperandType);
+                self.expect(right, ValueType::Number, ErrorType::InvalidOperandType);
+                Some(ValueType::Number)
+            },
+            TreeNode::Negation{arg,..} => {
+                self.expect(arg, ValueType::Number, ErrorType::InvalidOperandType);
+                Some(ValueType::Number)
+            },
+            TreeNode::Less{left,right,..} |
+            TreeNode::Greater{left,right,..} |
+            TreeNode::LessEq{left,right,..} |
+            TreeNode::GreaterEq{left,right,..} => {
+                self.expect(left, ValueType::Number, ErrorType::InvalidOperandType);
+                self.expect(right, ValueType::Number, ErrorType::InvalidOperandType);
+                Some(ValueType::Boolean)
+            },
+            TreeNode::EqTo{left,right,..} |
+            TreeNode::NeqTo{left,right,..} => {
+                //Anything can be compared to something of the same type, or to none
+                if let (Some(left_type), Some(right_type)) = (self.infer(left), self.infer(right)) {
+                    if left_type != right_type {
+                        self.errors.push(Error::at(ErrorType::InvalidOperandType, &right.token()));
+                    }
+                }
+                Some(ValueType::Boolean)
+            },
+            TreeNode::And{left,right,..} |
+            TreeNode::Or{left,right,..} => {
+                self.expect(left, ValueType::Boolean, ErrorType::InvalidOperandType);
+                self.expect(right, ValueType::Boolean, ErrorType::InvalidOperandType);
+                Some(ValueType::Boolean)
+            },
+            TreeNode::Not{arg,..} => {
+                self.expect(arg, ValueType::Boolean, ErrorType::InvalidOperandType);
+                Some(ValueType::Boolean)
+            },
+            TreeNode::Parens{val,..} => self.infer(val),
+            TreeNode::Statement{expression,..} => {
+                self.infer(expression);
+                None
+            },
+            _ => None
+        }
+    }
+
+    //Reports node if its type is known and isn't expected
+    fn expect(&mut self, node: &TreeNode<'a>, expected: ValueType, error_type: ErrorType) {
+        if let Some(found) = self.infer(node) {
+            if found != expected {
+                self.errors.push(Error::at(error_type, &node.token()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TypeChecker,Error,ErrorType};
+    use super::super::Parser;
+
+    fn check(text: &str) -> Vec<Error> {
+        let mut parser = Parser::new(text);
+        let program = parser.parse().unwrap();
+        TypeChecker::new().check(program.statements())
+    }
+
+    #[test]
+    fn declarations_match_their_type() {
+        assert_eq!(check(
+            "There is a number called X, it is 1 + 2 * 3. \
+            There is a boolean called Y, it is X is less than 4 and not false. \
+            There is a number called Z?"
+        ), []);
+        assert_eq!(check("There is a number called X, it is true."),
+            [Error::new(ErrorType::TypeMismatch, 1, 35).with_span(34, 38)]);
+        assert_eq!(check("There is a boolean called X, it is 2 + 2."),
+            [Error::new(ErrorType::TypeMismatch, 1, 38).with_span(37, 38)]);
+    }
+
+    #[test]
+    fn assignments_keep_the_declared_type() {
+        assert_eq!(check("There is a boolean called X, it is true. X, it is false."), []);
+        assert_eq!(check("There is a boolean called X, it is true. X, it is 3."),
+            [Error::new(ErrorType::TypeMismatch, 1, 51).with_span(50, 51)]);
+    }
+
+    #[test]
+    fn operators_need_matching_operands() {
+        assert_eq!(check("1 + true."), [Error::new(ErrorType::InvalidOperandType, 1, 5).with_span(4, 8)]);
+        assert_eq!(check("-false."), [Error::new(ErrorType::InvalidOperandType, 1, 2).with_span(1, 6)]);
+        assert_eq!(check("1 and true."), [Error::new(ErrorType::InvalidOperandType, 1, 1).with_span(0, 1)]);
+        assert_eq!(check("1 is true."), [Error::new(ErrorType::InvalidOperandType, 1, 6).with_span(5, 9)]);
+        assert_eq!(check("1 is none."), []);
+    }
+
+    #[test]
+    fn if_condition_is_boolean() {
+        assert_eq!(check("If 1 is 1, 2."), []);
+        assert_eq!(check("If 1 + 1, 2."), [Error::new(ErrorType::TypeMismatch, 1, 6).with_span(5, 6)]);
+        assert_eq!(check("If false, 1; if 2, 3; otherwise 4."), [Error::new(ErrorType::TypeMismatch, 1, 17).with_span(16, 17)]);
+    }
+}